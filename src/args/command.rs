@@ -0,0 +1,346 @@
+use clap::{Subcommand, ValueEnum};
+
+use crate::grammar::{
+    latex::LatexFormatOutputFormatDescriptor,
+    notation::{Notation, NotationProfile},
+    parse_structs::ParsingTableKind,
+};
+use crate::output::dot::DotStyle;
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Generate the LaTeX report of the grammar, with the selected sections
+    Report {
+        #[command(flatten)]
+        sections: ReportSections,
+
+        /// Output format
+        #[arg(long, value_enum)]
+        format: Option<Format>,
+    },
+
+    /// Generate the parsing tables of the grammar
+    Tables {
+        /// Parsing table to generate, may be repeated
+        #[arg(short, long = "table", value_enum, default_values_t = [ParsingTableKind::Lr0, ParsingTableKind::Slr1])]
+        tables: Vec<ParsingTableKind>,
+
+        /// Output format
+        #[arg(long, value_enum)]
+        format: Option<Format>,
+    },
+
+    /// Generate the characteristic automaton of the grammar
+    Automaton {
+        /// Kind of automaton to generate
+        #[arg(short, long, value_enum, default_value_t = AutomatonKind::Lr0)]
+        kind: AutomatonKind,
+
+        /// Output format
+        #[arg(long, value_enum)]
+        format: Option<Format>,
+    },
+
+    /// Generate the FIRST and FOLLOW sets of the non terminals
+    FirstFollow {
+        /// Output format
+        #[arg(long, value_enum)]
+        format: Option<Format>,
+    },
+
+    /// Simulate the parsing of a sentence, showing the stack and the input at each step
+    Parse {
+        /// Sentence to parse: terminals separated by whitespace, or one character per terminal
+        /// when it contains no whitespace
+        sentence: String,
+
+        /// Parsing table driving the parser
+        #[arg(short, long, value_enum, default_value_t = ParsingTableKind::Slr1)]
+        table: ParsingTableKind,
+
+        /// Output format
+        #[arg(long, value_enum)]
+        format: Option<Format>,
+    },
+
+    /// Print an equivalent grammar obtained by applying a transformation
+    Transform {
+        /// Transformation to apply
+        #[arg(value_enum)]
+        op: TransformOp,
+
+        /// Output format
+        #[arg(long, value_enum)]
+        format: Option<Format>,
+    },
+
+    /// Classify the grammar (regular, LL(1), LR(0), SLR(1), LALR(1), LR(1), ambiguous)
+    Classify {
+        /// Output format
+        #[arg(long, value_enum)]
+        format: Option<Format>,
+    },
+
+    /// Generate the shortest sentences of the language, one per line
+    Generate {
+        /// Maximum number of sentences to generate
+        #[arg(short, long, default_value_t = 10)]
+        count: usize,
+
+        /// Maximum length (in terminals) of the generated sentences
+        #[arg(short, long, default_value_t = 8)]
+        max_length: usize,
+
+        /// Output format
+        #[arg(long, value_enum)]
+        format: Option<Format>,
+    },
+}
+
+// Sections of the LaTeX report, all of them being included when none is selected (a plain
+// comment, as clap would otherwise use it as the about of the commands flattening it)
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct ReportSections {
+    /// Include Grammophone link
+    #[arg(long, default_value_t = false)]
+    pub grammophone_link: bool,
+
+    /// Include Graphviz link
+    #[arg(long, default_value_t = false)]
+    pub graphviz_link: bool,
+
+    /// Include grammar definition
+    #[arg(long, default_value_t = false)]
+    pub grammar_definition: bool,
+
+    /// Include the list of the productions, numbered as in the reduce actions
+    #[arg(long, default_value_t = false)]
+    pub production_list: bool,
+
+    /// Include LR(0) parsing table
+    #[arg(long, default_value_t = false)]
+    pub lr0_parsing_table: bool,
+
+    /// Include SLR(1) parsing table
+    #[arg(long, default_value_t = false)]
+    pub slr1_parsing_table: bool,
+
+    /// Include first-follow set
+    #[arg(long, default_value_t = false)]
+    pub first_follow_set: bool,
+
+    /// Include the LR(0) automaton drawn with TikZ
+    #[arg(long, default_value_t = false)]
+    pub lr0_automaton: bool,
+
+    /// Include the canonical collection of the LR(0) item sets
+    #[arg(long, default_value_t = false)]
+    pub item_sets: bool,
+
+    /// Include the GOTO function of the LR(0) automaton as a table
+    #[arg(long, default_value_t = false)]
+    pub goto_table: bool,
+
+    /// Include all of the possible latex format options
+    #[arg(long, default_value_t = false)]
+    pub all: bool,
+}
+
+impl ReportSections {
+    pub fn get_descriptor(&self) -> LatexFormatOutputFormatDescriptor {
+        if self.all {
+            LatexFormatOutputFormatDescriptor::ALL
+        } else if !(self.grammophone_link
+            || self.graphviz_link
+            || self.grammar_definition
+            || self.production_list
+            || self.lr0_parsing_table
+            || self.slr1_parsing_table
+            || self.first_follow_set
+            || self.lr0_automaton
+            || self.item_sets
+            || self.goto_table)
+        {
+            LatexFormatOutputFormatDescriptor::FULL
+        } else {
+            LatexFormatOutputFormatDescriptor {
+                grammophone_link: self.grammophone_link,
+                graphviz_link: self.graphviz_link,
+                grammar_definition: self.grammar_definition,
+                production_list: self.production_list,
+                lr0_parsing_table: self.lr0_parsing_table,
+                slr1_parsing_table: self.slr1_parsing_table,
+                first_follow_set: self.first_follow_set,
+                lr0_automaton: self.lr0_automaton,
+                item_sets: self.item_sets,
+                goto_table: self.goto_table,
+            }
+        }
+    }
+
+    /// Whether any of the section flags has been given
+    pub fn is_any_set(&self) -> bool {
+        self.all
+            || self.grammophone_link
+            || self.graphviz_link
+            || self.grammar_definition
+            || self.production_list
+            || self.lr0_parsing_table
+            || self.slr1_parsing_table
+            || self.first_follow_set
+            || self.lr0_automaton
+            || self.item_sets
+            || self.goto_table
+    }
+}
+
+// Notation of the symbols, items, states and actions: a profile whose choices can be overridden
+// one by one (a plain comment, for the same reason as the one of `ReportSections`)
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct NotationOptions {
+    /// Notation of the textbook the output should follow
+    #[arg(long, value_enum, global = true, default_value_t = NotationProfile::default())]
+    pub notation: NotationProfile,
+
+    /// Symbol written in place of an empty body
+    #[arg(long, global = true)]
+    pub epsilon: Option<String>,
+
+    /// Arrow separating the driver of a production from its body
+    #[arg(long, global = true)]
+    pub arrow: Option<String>,
+
+    /// Symbol marking the position of the dot in an item
+    #[arg(long, global = true)]
+    pub item_dot: Option<String>,
+
+    /// Prefix of the names of the states, e.g. `I` for `I0`, `I1`, ...
+    #[arg(long, global = true)]
+    pub state_prefix: Option<String>,
+
+    /// Number of the first production in the reduce actions
+    #[arg(long, global = true)]
+    pub first_production: Option<usize>,
+}
+
+impl NotationOptions {
+    pub fn get_notation(&self) -> Notation {
+        let profile = Notation::from_profile(self.notation);
+        Notation {
+            epsilon: self.epsilon.clone().unwrap_or(profile.epsilon),
+            arrow: self.arrow.clone().unwrap_or(profile.arrow),
+            item_dot: self.item_dot.clone().unwrap_or(profile.item_dot),
+            state_prefix: self.state_prefix.clone().unwrap_or(profile.state_prefix),
+            first_production: self.first_production.unwrap_or(profile.first_production),
+        }
+    }
+}
+
+// Style of the automata written in DOT (a plain comment, for the same reason as the one of
+// `ReportSections`)
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct DotOptions {
+    /// Label the states of the DOT automata with their kernel only
+    #[arg(long, global = true, default_value_t = false)]
+    pub kernel_only: bool,
+
+    /// Lay out the DOT automata left to right
+    #[arg(long, global = true, default_value_t = false)]
+    pub left_to_right: bool,
+
+    /// Draw the accepting states of the DOT automata with a bold border
+    #[arg(long, global = true, default_value_t = false)]
+    pub mark_accepting: bool,
+
+    /// Add an edge by `$` from the accepting states of the DOT automata to an `accept` node
+    #[arg(long, global = true, default_value_t = false)]
+    pub accept_edge: bool,
+
+    /// Highlight the complete items of the DOT automata
+    #[arg(long, global = true, default_value_t = false)]
+    pub highlight_reduces: bool,
+
+    /// Write the states of the DOT automata with HTML-like labels instead of records
+    #[arg(long, global = true, default_value_t = false)]
+    pub html_labels: bool,
+}
+
+impl DotOptions {
+    pub fn get_style(&self) -> DotStyle {
+        DotStyle {
+            kernel_only: self.kernel_only,
+            left_to_right: self.left_to_right,
+            mark_accepting: self.mark_accepting,
+            accept_edge: self.accept_edge,
+            highlight_reduces: self.highlight_reduces,
+            html_labels: self.html_labels,
+        }
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    Markdown,
+    Html,
+    Latex,
+    Dot,
+    Svg,
+    Mermaid,
+}
+
+impl std::fmt::Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Format::Text => write!(f, "text"),
+            Format::Json => write!(f, "json"),
+            Format::Markdown => write!(f, "markdown"),
+            Format::Html => write!(f, "html"),
+            Format::Latex => write!(f, "latex"),
+            Format::Dot => write!(f, "dot"),
+            Format::Svg => write!(f, "svg"),
+            Format::Mermaid => write!(f, "mermaid"),
+        }
+    }
+}
+
+/// Borders of the tables written as text
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableStyle {
+    /// Columns aligned with spaces, without borders
+    Plain,
+    /// Borders drawn with `+`, `-` and `|`
+    Ascii,
+    /// Borders drawn with the Unicode box drawing characters
+    Unicode,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Colour the output when it is written to a terminal and `NO_COLOR` is not set
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutomatonKind {
+    /// The automaton of the sets of LR(0) items
+    Lr0,
+    /// The automaton of the sets of LR(1) items
+    Lr1,
+    /// The non deterministic automaton of the LR(0) items, turned into the LR(0) one by the
+    /// subset construction
+    ItemNfa,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransformOp {
+    /// Desugar the EBNF constructs into plain productions
+    Desugar,
+    /// Remove the left recursion
+    RemoveLeftRecursion,
+    /// Factor the common prefixes of the alternatives of each non terminal
+    LeftFactor,
+}
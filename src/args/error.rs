@@ -1,17 +1,302 @@
+use std::{fmt, io, process::ExitCode};
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum GrammarDecodeError {
-    InvalidFormat(String),
+    /// Errors found in the grammar definition, in the order in which they appear
+    InvalidFormat(Vec<SyntaxError>),
+    /// The grammar definition contains no production
+    EmptyGrammar,
     ParseError(String),
 }
 
+/// An error located in the grammar definition
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntaxError {
+    pub kind: SyntaxErrorKind,
+    /// 1 based line of the offending text
+    pub line: usize,
+    /// 1 based column (in characters) of the offending text
+    pub column: usize,
+    /// Offending text, underlined when the error is rendered
+    pub text: String,
+    /// Whole line containing the offending text
+    pub source_line: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyntaxErrorKind {
+    UnterminatedQuote,
+    EmptyQuote,
+    /// A token that can't appear at this point, e.g. a `->` inside a body
+    UnexpectedToken {
+        expected: String,
+        found: String,
+    },
+    /// More than one symbol on the left side of `->`
+    NotFree {
+        driver: String,
+    },
+    MissingArrow,
+    /// An unquoted symbol made of more than one character which is neither a declared terminal
+    /// nor a non terminal
+    MultiCharSymbol {
+        symbol: String,
+    },
+    UnknownDirective {
+        directive: String,
+    },
+    MissingTerminal {
+        directive: String,
+    },
+    InvalidStart,
+    UndefinedStart {
+        symbol: String,
+    },
+    UnclosedGroup {
+        expected: char,
+    },
+    PrecInsideGroup,
+    /// An EBNF group with no symbols, e.g. `[ ]` or `( | )`
+    EmptyGroup,
+    /// A non terminal used in a body which is not the driver of any production
+    UndefinedNonTerminal {
+        symbol: String,
+    },
+    /// `$` and `@`, quoted or not, which stand for the end of input and the augmented start
+    ReservedSymbol {
+        symbol: String,
+    },
+    /// A non terminal which derives no terminal string, e.g. `A` in `A -> a A .`
+    NonProductive {
+        symbol: String,
+    },
+}
+
+impl fmt::Display for SyntaxErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SyntaxErrorKind::UnterminatedQuote => write!(f, "unterminated quoted terminal"),
+            SyntaxErrorKind::EmptyQuote => write!(f, "empty quoted terminal"),
+            SyntaxErrorKind::UnexpectedToken { expected, found } => {
+                write!(f, "expected {}, found {}", expected, found)
+            }
+            SyntaxErrorKind::NotFree { driver } => write!(
+                f,
+                "grammar is not free: expected one symbol on the left side of `->`, found `{}`",
+                driver
+            ),
+            SyntaxErrorKind::MissingArrow => write!(f, "expected `->` after the driver"),
+            SyntaxErrorKind::MultiCharSymbol { symbol } => write!(
+                f,
+                "unknown symbol `{}`: terminals longer than one character must be quoted or \
+                 declared with %token",
+                symbol
+            ),
+            SyntaxErrorKind::UnknownDirective { directive } => {
+                write!(f, "unknown directive `%{}`", directive)
+            }
+            SyntaxErrorKind::MissingTerminal { directive } => {
+                write!(f, "expected a terminal after `%{}`", directive)
+            }
+            SyntaxErrorKind::InvalidStart => write!(f, "%start expects exactly one non terminal"),
+            SyntaxErrorKind::UndefinedStart { symbol } => write!(
+                f,
+                "the %start symbol `{}` is not the driver of any production",
+                symbol
+            ),
+            SyntaxErrorKind::UnclosedGroup { expected } => {
+                write!(f, "unclosed EBNF group, expected `{}`", expected)
+            }
+            SyntaxErrorKind::PrecInsideGroup => write!(f, "%prec can't be used inside EBNF groups"),
+            SyntaxErrorKind::EmptyGroup => write!(f, "empty EBNF group"),
+            SyntaxErrorKind::UndefinedNonTerminal { symbol } => write!(
+                f,
+                "non terminal `{}` is not the driver of any production",
+                symbol
+            ),
+            SyntaxErrorKind::ReservedSymbol { symbol } => write!(
+                f,
+                "`{}` is reserved for the {} and can't be used as a terminal",
+                symbol,
+                if symbol == "$" {
+                    "end of input"
+                } else {
+                    "augmented start symbol"
+                }
+            ),
+            SyntaxErrorKind::NonProductive { symbol } => {
+                write!(f, "non terminal `{}` derives no terminal string", symbol)
+            }
+        }
+    }
+}
+
+/// Renders the error rustc-style, underlining the offending text with carets:
+///
+/// ```text
+/// error: expected `->` after the driver
+///  --> 1:3
+///   |
+/// 1 | A B -> c .
+///   |   ^
+/// ```
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let line_number = self.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+        // Tabs are kept so that the carets line up with the source line
+        let padding: String = self
+            .source_line
+            .chars()
+            .take(self.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let carets = "^".repeat(self.text.chars().count().max(1));
+
+        writeln!(f, "error: {}", self.kind)?;
+        writeln!(f, "{}--> {}:{}", gutter, self.line, self.column)?;
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", line_number, self.source_line)?;
+        write!(f, "{} | {}{}", gutter, padding, carets)
+    }
+}
+
+impl fmt::Display for GrammarDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GrammarDecodeError::InvalidFormat(errors) => {
+                for error in errors {
+                    writeln!(f, "{}\n", error)?;
+                }
+                match errors.len() {
+                    1 => write!(
+                        f,
+                        "error: could not decode the grammar due to 1 previous error"
+                    ),
+                    n => write!(
+                        f,
+                        "error: could not decode the grammar due to {} previous errors",
+                        n
+                    ),
+                }
+            }
+            GrammarDecodeError::EmptyGrammar => write!(f, "error: the grammar has no productions"),
+            GrammarDecodeError::ParseError(message) => write!(f, "error: {}", message),
+        }
+    }
+}
+
+/// A grammar transformation which can't be applied to the grammar
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransformError {
+    /// The non terminal only derives itself, e.g. `A -> B . B -> A .`
+    Cycle { non_term: String },
+    /// All of the bodies of the non terminal start with itself, e.g. `A -> A a .`
+    OnlyLeftRecursive { non_term: String },
+    /// The non terminal is left recursive through a nullable prefix, e.g. `A -> B A a` with `B`
+    /// nullable
+    HiddenLeftRecursion { non_term: String },
+}
+
+impl fmt::Display for TransformError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransformError::Cycle { non_term } => write!(
+                f,
+                "error: can't remove the left recursion: `{}` only derives itself",
+                non_term
+            ),
+            TransformError::OnlyLeftRecursive { non_term } => write!(
+                f,
+                "error: can't remove the left recursion: every body of `{}` starts with `{}`",
+                non_term, non_term
+            ),
+            TransformError::HiddenLeftRecursion { non_term } => write!(
+                f,
+                "error: can't remove the left recursion of `{}`, hidden behind a nullable prefix",
+                non_term
+            ),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum ArgsError {
     ArgsParsingError(clap::Error),
-    ArgsConflict,
-    MissingRequiredArg,
+    ArgsConflict(String),
+    MissingRequiredArg(String),
 }
 
-#[derive(Debug, Clone, PartialEq)]
+impl fmt::Display for ArgsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArgsError::ArgsParsingError(err) => write!(f, "{}", err),
+            ArgsError::ArgsConflict(message) | ArgsError::MissingRequiredArg(message) => {
+                write!(f, "error: {}", message)
+            }
+        }
+    }
+}
+
+/// Error returned by any stage of the command line tool, from argument parsing to output
+#[derive(Debug)]
 pub enum InitError {
+    ArgsError(ArgsError),
     GrammarDecodeError(GrammarDecodeError),
+    TransformError(TransformError),
+    /// The output could not be written, e.g. because stdout was closed
+    OutputError(io::Error),
+}
+
+impl InitError {
+    /// Exit code of the process when failing with this error:
+    /// - 2 for invalid command line arguments, as clap does;
+    /// - 3 when the input could not be read or decoded;
+    /// - 4 when the grammar definition is invalid;
+    /// - 5 when the output could not be written;
+    /// - 6 when the grammar can't be transformed as requested.
+    pub fn exit_code(&self) -> ExitCode {
+        ExitCode::from(match self {
+            InitError::ArgsError(_) => 2,
+            InitError::GrammarDecodeError(GrammarDecodeError::ParseError(_)) => 3,
+            InitError::GrammarDecodeError(_) => 4,
+            InitError::OutputError(_) => 5,
+            InitError::TransformError(_) => 6,
+        })
+    }
+}
+
+impl fmt::Display for InitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InitError::ArgsError(err) => write!(f, "{}", err),
+            InitError::GrammarDecodeError(err) => write!(f, "{}", err),
+            InitError::TransformError(err) => write!(f, "{}", err),
+            InitError::OutputError(err) => write!(f, "error: failed to write the output: {}", err),
+        }
+    }
+}
+
+impl From<ArgsError> for InitError {
+    fn from(err: ArgsError) -> Self {
+        InitError::ArgsError(err)
+    }
+}
+
+impl From<GrammarDecodeError> for InitError {
+    fn from(err: GrammarDecodeError) -> Self {
+        InitError::GrammarDecodeError(err)
+    }
+}
+
+impl From<TransformError> for InitError {
+    fn from(err: TransformError) -> Self {
+        InitError::TransformError(err)
+    }
+}
+
+impl From<io::Error> for InitError {
+    fn from(err: io::Error) -> Self {
+        InitError::OutputError(err)
+    }
 }
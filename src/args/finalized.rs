@@ -1,75 +1,176 @@
-use std::fs;
+use std::io::IsTerminal;
 
-use base64::{Engine as _, engine::general_purpose};
-use clap::{ArgGroup, Parser};
+use crate::grammar::{latex::LatexFormatOutputFormatDescriptor, parse_structs::ParsingTableKind};
+use crate::output::RenderOptions;
 
-use crate::grammar::latex::LatexFormatOutputFormatDescriptor;
-
-use super::Args;
+use super::{
+    Args,
+    command::{AutomatonKind, ColorChoice, Command, Format, TransformOp},
+    error::ArgsError,
+};
 
 pub struct FinalizedArgs {
     pub input_type: InputType,
     pub output_type: OutputType,
-    pub latex_format_descriptor: Option<LatexFormatOutputFormatDescriptor>,
+    pub format: Format,
+    pub render_options: RenderOptions,
 }
 
 pub enum InputType {
     File(String),
     Base64(String),
+    Stdin,
 }
 
 pub enum OutputType {
-    Latex,
-    Dot,
+    Report(LatexFormatOutputFormatDescriptor),
+    Tables(Vec<ParsingTableKind>),
+    Automaton(AutomatonKind),
+    FirstFollow,
+    Parse {
+        sentence: String,
+        table: ParsingTableKind,
+    },
+    Transform(TransformOp),
+    Classify,
+    Generate {
+        count: usize,
+        max_length: usize,
+    },
 }
 
-impl Args {
-    pub fn finalize(&self) -> FinalizedArgs {
-        // Se l'utente non ha specificato né --latex né --dot, abilito --latex di default
+impl OutputType {
+    /// Format used when none is given with `--format`
+    pub fn get_default_format(&self) -> Format {
+        match self {
+            OutputType::Report(_)
+            | OutputType::Tables(_)
+            | OutputType::FirstFollow
+            | OutputType::Classify => Format::Latex,
+            OutputType::Automaton(_) => Format::Dot,
+            OutputType::Parse { .. } | OutputType::Transform(_) | OutputType::Generate { .. } => {
+                Format::Text
+            }
+        }
+    }
 
+    pub fn get_command_name(&self) -> &'static str {
+        match self {
+            OutputType::Report(_) => "report",
+            OutputType::Tables(_) => "tables",
+            OutputType::Automaton(_) => "automaton",
+            OutputType::FirstFollow => "first-follow",
+            OutputType::Parse { .. } => "parse",
+            OutputType::Transform(_) => "transform",
+            OutputType::Classify => "classify",
+            OutputType::Generate { .. } => "generate",
+        }
+    }
+}
+
+impl Args {
+    pub fn finalize(&self) -> Result<FinalizedArgs, ArgsError> {
         let input_type = match (&self.file, &self.base64) {
             (None, Some(base64)) => InputType::Base64(base64.clone()),
+            (Some(file), None) if file == "-" => InputType::Stdin,
             (Some(file), None) => InputType::File(file.clone()),
-            (Some(_), Some(_)) => panic!("Error: --file and --base-64 are mutually exclusive"),
-            _ => panic!("Error: you should provide either --file and --base-64"),
+            (Some(_), Some(_)) => {
+                return Err(ArgsError::ArgsConflict(String::from(
+                    "--file and --base-64 are mutually exclusive",
+                )));
+            }
+            (None, None) => InputType::Stdin,
         };
 
-        let output_type = match (self.latex, self.dot) {
-            (true, false) => OutputType::Latex,
-            (false, true) => OutputType::Dot,
-            (false, false) => OutputType::Dot,
-            (true, true) => panic!("Error: --latex and --dot are mutually exclusive"),
+        let (output_type, format) = match &self.command {
+            Some(command) => {
+                if self.latex
+                    || self.dot
+                    || self.classify
+                    || self.desugar
+                    || self.sections.is_any_set()
+                {
+                    return Err(ArgsError::ArgsConflict(String::from(
+                        "the legacy output flags can't be used together with a subcommand",
+                    )));
+                }
+                Self::finalize_command(command)
+            }
+            None => (self.finalize_legacy_flags()?, None),
         };
 
-        let latex_format_descriptor = if self.all {
-            LatexFormatOutputFormatDescriptor::FULL
-        } else if !self.grammophone_link
-            && !self.grammophone_link
-            && !self.grammar_definition
-            && !self.lr0_parsing_table
-            && !self.slr1_parsing_table
-            && !self.first_follow_set
-        {
-            LatexFormatOutputFormatDescriptor::FULL
-        } else {
-            LatexFormatOutputFormatDescriptor {
-                grammophone_link: self.grammophone_link,
-                graphviz_link: self.grammophone_link,
-                grammar_definition: self.grammar_definition,
-                lr0_parsing_table: self.lr0_parsing_table,
-                slr1_parsing_table: self.slr1_parsing_table,
-                first_follow_set: self.first_follow_set,
+        // Whether the output can be written in the format is known by its backend
+        let format = format.unwrap_or(output_type.get_default_format());
+
+        let color = match self.color {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                std::env::var_os("NO_COLOR").is_none() && std::io::stdout().is_terminal()
             }
         };
 
-        FinalizedArgs {
+        Ok(FinalizedArgs {
             input_type,
             output_type,
-            latex_format_descriptor: if self.latex || self.dot {
-                Some(latex_format_descriptor)
-            } else {
-                None
+            format,
+            render_options: RenderOptions {
+                table_style: self.table_style,
+                color,
+                standalone: self.standalone,
+                language: self.lang,
+                notation: self.notation.get_notation(),
+                dot: self.dot_options.get_style(),
             },
+        })
+    }
+
+    fn finalize_command(command: &Command) -> (OutputType, Option<Format>) {
+        match command {
+            Command::Report { sections, format } => {
+                (OutputType::Report(sections.get_descriptor()), *format)
+            }
+            Command::Tables { tables, format } => (OutputType::Tables(tables.clone()), *format),
+            Command::Automaton { kind, format } => (OutputType::Automaton(*kind), *format),
+            Command::FirstFollow { format } => (OutputType::FirstFollow, *format),
+            Command::Parse {
+                sentence,
+                table,
+                format,
+            } => (
+                OutputType::Parse {
+                    sentence: sentence.clone(),
+                    table: *table,
+                },
+                *format,
+            ),
+            Command::Transform { op, format } => (OutputType::Transform(*op), *format),
+            Command::Classify { format } => (OutputType::Classify, *format),
+            Command::Generate {
+                count,
+                max_length,
+                format,
+            } => (
+                OutputType::Generate {
+                    count: *count,
+                    max_length: *max_length,
+                },
+                *format,
+            ),
+        }
+    }
+
+    /// Maps the flags predating the subcommands onto the equivalent subcommand
+    fn finalize_legacy_flags(&self) -> Result<OutputType, ArgsError> {
+        match (self.latex, self.dot, self.classify, self.desugar) {
+            (true, false, false, false) => Ok(OutputType::Report(self.sections.get_descriptor())),
+            (false, true, false, false) => Ok(OutputType::Automaton(AutomatonKind::Lr0)),
+            (false, false, true, false) => Ok(OutputType::Classify),
+            (false, false, false, true) => Ok(OutputType::Transform(TransformOp::Desugar)),
+            (false, false, false, false) => Ok(OutputType::Automaton(AutomatonKind::Lr0)),
+            _ => Err(ArgsError::ArgsConflict(String::from(
+                "--latex, --dot, --classify and --desugar are mutually exclusive",
+            ))),
         }
     }
 }
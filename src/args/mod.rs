@@ -1,82 +1,74 @@
-pub mod finalized;
+pub mod command;
 pub mod error;
+pub mod finalized;
 
-use std::fs;
-
-use base64::{Engine as _, engine::general_purpose};
 use clap::{ArgGroup, Parser};
-use error::GrammarDecodeError;
+use command::{ColorChoice, Command, DotOptions, NotationOptions, ReportSections, TableStyle};
 
+use crate::grammar::locale::Language;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 #[command(group(
-    ArgGroup::new("input")
-        .required(true)
-        .multiple(false)
-        .args(["file", "base64"]),
-
-    ),
-    group(
         ArgGroup::new("output")
-            .required(false) 
+            .required(false)
             .multiple(false)
-            .args(["latex", "dot"]),
-    ),
-    group(
-        ArgGroup::new("latex-format")
-            .required(false) 
-            .multiple(true)
-            .args(["grammophone_link", "graphviz_link", "grammar_definition", "lr0_parsing_table", "slr1_parsing_table", "first_follow_set", "all"]),
+            .args(["latex", "dot", "classify", "desugar"]),
     )
 )]
-
 pub struct Args {
-    /// Input file path
-    #[arg(short = 'f', long, group = "input")]
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Input file path, `-` to read the grammar from stdin (the default when no input is given)
+    #[arg(short = 'f', long, global = true, conflicts_with = "base64")]
     pub file: Option<String>,
 
     /// Base64 encoded input
-    #[arg(long = "base-64", group = "input")]
+    #[arg(long = "base-64", global = true)]
     pub base64: Option<String>,
 
-    /// Generate LaTeX souce code
-    #[arg(long, default_value_t = false, group = "output")]
-    pub latex: bool,
-
-    /// Generate DOT notation for lr0 automaton
-    #[arg(long, default_value_t = false,  group = "output")]
-    pub dot: bool,
+    /// Borders of the tables written as text
+    #[arg(long, value_enum, global = true, default_value_t = TableStyle::Unicode)]
+    pub table_style: TableStyle,
 
-    /// Include Grammophone link
-    #[arg(long, default_value_t = false, group="latex-format")]
-    grammophone_link: bool,
-    ///
-    /// Include Graphviz link
-    #[arg(long, default_value_t = false, group="latex-format")]
-    graphviz_link: bool,
+    /// Highlight the conflicting cells of the tables written as text with ANSI colours
+    #[arg(long, value_enum, global = true, default_value_t = ColorChoice::Auto)]
+    pub color: ColorChoice,
 
-    /// Include grammar definition
-    #[arg(long, default_value_t = false, group="latex-format")]
-    grammar_definition: bool,
+    /// Write a complete LaTeX document, which compiles on its own, instead of fragments
+    #[arg(long, default_value_t = false, global = true)]
+    pub standalone: bool,
 
-    /// Include LR(0) parsing table
-    #[arg(long, default_value_t = false, group="latex-format")]
-    lr0_parsing_table: bool,
+    /// Language of the LaTeX output: captions, headers, links, classification reasons and parse
+    /// trace messages
+    #[arg(long, value_enum, global = true, default_value_t = Language::default())]
+    pub lang: Language,
 
-    /// Include SLR(1) parsing table
-    #[arg(long, default_value_t = false, group="latex-format")]
-    slr1_parsing_table: bool,
+    #[command(flatten)]
+    pub notation: NotationOptions,
 
-    /// Include first-follow set
-    #[arg(long, default_value_t = false, group="latex-format")]
-    first_follow_set: bool,
+    #[command(flatten)]
+    pub dot_options: DotOptions,
 
-    /// Include all of the possible latex format options
-    #[arg(long, default_value_t = false, group="latex-format")]
-    all: bool,
+    /// Generate LaTeX souce code (alias of `report`)
+    #[arg(long, default_value_t = false, group = "output")]
+    pub latex: bool,
 
-}
+    /// Generate DOT notation for lr0 automaton (alias of `automaton`)
+    #[arg(long, default_value_t = false, group = "output")]
+    pub dot: bool,
 
+    /// Generate a LaTeX table classifying the grammar (alias of `classify`)
+    #[arg(long, default_value_t = false, group = "output")]
+    pub classify: bool,
 
+    /// Print the grammar definition with the EBNF constructs desugared into plain productions
+    /// (alias of `transform desugar`)
+    #[arg(long, default_value_t = false, group = "output")]
+    pub desugar: bool,
 
+    // Sections of the report generated by `--latex`
+    #[command(flatten)]
+    sections: ReportSections,
+}
@@ -0,0 +1,734 @@
+use std::collections::{HashMap, VecDeque};
+
+use serde::Serialize;
+
+use super::{
+    grammar::Grammar, latex::escape_latex, locale::Language, notation::Notation,
+    parse_structs::Action,
+};
+
+/// Maximum length of the sentences explored while looking for an ambiguity witness
+const AMBIGUITY_MAX_SENTENCE_LEN: usize = 8;
+/// Maximum number of sentential forms explored while looking for an ambiguity witness
+const AMBIGUITY_MAX_EXPLORED_FORMS: usize = 50_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GrammarClass {
+    Regular,
+    Ll1,
+    Lr0,
+    Slr1,
+    Lalr1,
+    Lr1,
+    Ambiguous,
+}
+
+impl std::fmt::Display for GrammarClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GrammarClass::Regular => write!(f, "Regular"),
+            GrammarClass::Ll1 => write!(f, "LL(1)"),
+            GrammarClass::Lr0 => write!(f, "LR(0)"),
+            GrammarClass::Slr1 => write!(f, "SLR(1)"),
+            GrammarClass::Lalr1 => write!(f, "LALR(1)"),
+            GrammarClass::Lr1 => write!(f, "LR(1)"),
+            GrammarClass::Ambiguous => write!(f, "Ambiguous"),
+        }
+    }
+}
+
+/// A cell of a parsing table holding more than one entry
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "table", rename_all = "snake_case")]
+pub enum ConflictCell {
+    /// `M[non_term, term]` of the LL(1) table with the indices of its productions
+    Ll1 {
+        non_term: char,
+        term: char,
+        productions: Vec<usize>,
+    },
+    /// The cell of an LR table for the state and the symbol
+    Lr {
+        state: usize,
+        symbol: char,
+        actions: Vec<Action>,
+    },
+}
+
+impl ConflictCell {
+    /// The cell in English, e.g. `state s4 on a: s3/r1`
+    pub fn format(&self, grammar: &Grammar, notation: &Notation) -> String {
+        Language::En.conflict_cell(self, grammar, notation)
+    }
+}
+
+/// Why the grammar belongs (or not) to a class. Productions are referred to by their index in
+/// `Grammar::productions`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum VerdictReason {
+    NotLinear {
+        production: usize,
+    },
+    MixedLinearity {
+        right_linear: usize,
+        left_linear: usize,
+    },
+    LeftLinear,
+    RightLinear,
+    NoConflicts,
+    /// The conflicting cells of the table, row by row
+    Conflicts {
+        cells: Vec<ConflictCell>,
+    },
+    /// The grammar belongs to a class whose grammars are never ambiguous
+    UnambiguousClass {
+        class: GrammarClass,
+    },
+    /// A sentence with two distinct leftmost derivations
+    Witness {
+        sentence: Vec<char>,
+        derivations: (Vec<usize>, Vec<usize>),
+    },
+    NoWitness {
+        max_sentence_len: usize,
+    },
+}
+
+impl VerdictReason {
+    /// The reason in English
+    pub fn format(&self, grammar: &Grammar, notation: &Notation) -> String {
+        Language::En.verdict_reason(self, grammar, notation)
+    }
+}
+
+/// Outcome of the membership test of a grammar in a class. `member` is `None` when the test is
+/// inconclusive (e.g. no ambiguity witness was found within the search bounds).
+#[derive(Debug, Clone, Serialize)]
+pub struct ClassVerdict {
+    pub class: GrammarClass,
+    pub member: Option<bool>,
+    pub reason: VerdictReason,
+}
+
+impl ClassVerdict {
+    pub fn member_str(&self) -> &'static str {
+        match self.member {
+            Some(true) => "Yes",
+            Some(false) => "No",
+            None => "?",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GrammarClassification {
+    pub verdicts: Vec<ClassVerdict>,
+}
+
+/// A sentence with two distinct leftmost derivations. Both derivations lead to
+/// `sentential_form`, from which `sentence` is derived in the same way.
+#[derive(Debug, Clone)]
+pub struct AmbiguityWitness {
+    pub sentence: Vec<char>,
+    pub sentential_form: Vec<char>,
+    pub derivations: (Vec<usize>, Vec<usize>),
+}
+
+impl GrammarClassification {
+    /// Returns whether the grammar belongs to the given class, `None` if unknown
+    pub fn is(&self, class: GrammarClass) -> Option<bool> {
+        self.verdicts
+            .iter()
+            .find(|verdict| verdict.class == class)
+            .and_then(|verdict| verdict.member)
+    }
+
+    pub fn generate_latex_string(
+        &self,
+        grammar: &Grammar,
+        notation: &Notation,
+        language: Language,
+    ) -> String {
+        let mut rv = String::new();
+
+        rv.push_str("\\begin{table}[H]");
+        rv.push_str("\\centering");
+        rv.push_str("\\begin{tabular}{llp{0.6\\linewidth}}\n");
+        rv.push_str("\\toprule\n");
+        rv.push_str(format!("{}\\\\\n", language.classification_headers().join(" & ")).as_str());
+        rv.push_str("\\midrule\n");
+        for verdict in self.verdicts.iter() {
+            rv.push_str(
+                format!(
+                    "{} & {} & {}\\\\\n",
+                    language.class_name(verdict.class),
+                    language.maybe(verdict.member),
+                    escape_latex(&language.verdict_reason(&verdict.reason, grammar, notation))
+                )
+                .as_str(),
+            );
+        }
+        rv.push_str("\\bottomrule\n");
+        rv.push_str("\\end{tabular}\n");
+        rv.push_str(format!("\\caption{{{}}}", language.classification()).as_str());
+        rv.push_str("\\label{tab:classification}");
+        rv.push_str("\\end{table}");
+        rv
+    }
+
+    /// The verdicts in aligned columns
+    pub fn generate_text_string(&self, grammar: &Grammar, notation: &Notation) -> String {
+        let class_width = self
+            .verdicts
+            .iter()
+            .map(|verdict| verdict.class.to_string().chars().count())
+            .max()
+            .unwrap_or(0)
+            .max("Class".len());
+
+        let mut lines = vec![format!("{:<class_width$}  Member  Reason", "Class")];
+        for verdict in self.verdicts.iter() {
+            lines.push(format!(
+                "{:<class_width$}  {:<6}  {}",
+                verdict.class.to_string(),
+                verdict.member_str(),
+                verdict.reason.format(grammar, notation)
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+impl Grammar {
+    /// Tests the grammar against every supported class, reporting the reason of each failure
+    pub fn classify(&self) -> GrammarClassification {
+        let first_follow_set = self.get_first_follow_table();
+        let lr0_parsing_table = self.get_lr0_parsing_table();
+        let slr1_parsing_table =
+            self.get_slr1_parsing_table(Some(&lr0_parsing_table), Some(&first_follow_set));
+        let lalr1_parsing_table =
+            self.get_lalr1_parsing_table(Some(&lr0_parsing_table), Some(&first_follow_set));
+        let lr1_parsing_table = self.get_lr1_parsing_table(Some(&first_follow_set));
+        let ll1_parsing_table = self.get_ll1_parsing_table(Some(&first_follow_set));
+
+        let mut verdicts = vec![
+            self.regular_verdict(),
+            self.ll1_verdict(&ll1_parsing_table),
+            self.lr_verdict(GrammarClass::Lr0, &lr0_parsing_table),
+            self.lr_verdict(GrammarClass::Slr1, &slr1_parsing_table),
+            self.lr_verdict(GrammarClass::Lalr1, &lalr1_parsing_table),
+            self.lr_verdict(GrammarClass::Lr1, &lr1_parsing_table),
+        ];
+
+        let unambiguous_class = verdicts
+            .iter()
+            .find(|verdict| verdict.class != GrammarClass::Regular && verdict.member == Some(true))
+            .map(|verdict| verdict.class);
+
+        let ambiguity_verdict = match unambiguous_class {
+            Some(class) => ClassVerdict {
+                class: GrammarClass::Ambiguous,
+                member: Some(false),
+                reason: VerdictReason::UnambiguousClass { class },
+            },
+            None => match self.find_ambiguity_witness(AMBIGUITY_MAX_SENTENCE_LEN) {
+                Some(witness) => ClassVerdict {
+                    class: GrammarClass::Ambiguous,
+                    member: Some(true),
+                    reason: VerdictReason::Witness {
+                        sentence: witness.sentence,
+                        derivations: witness.derivations,
+                    },
+                },
+                None => ClassVerdict {
+                    class: GrammarClass::Ambiguous,
+                    member: None,
+                    reason: VerdictReason::NoWitness {
+                        max_sentence_len: AMBIGUITY_MAX_SENTENCE_LEN,
+                    },
+                },
+            },
+        };
+        verdicts.push(ambiguity_verdict);
+
+        GrammarClassification { verdicts }
+    }
+
+    fn regular_verdict(&self) -> ClassVerdict {
+        let mut right_linear_example = None;
+        let mut left_linear_example = None;
+
+        for (prod_index, production) in self.productions.iter().enumerate() {
+            let non_term_positions: Vec<usize> = production
+                .body
+                .iter()
+                .enumerate()
+                .filter(|(_, symbol)| self.non_terms.contains(symbol))
+                .map(|(position, _)| position)
+                .collect();
+
+            let is_right_linear = match non_term_positions.as_slice() {
+                [] => true,
+                [position] => *position == production.body.len() - 1,
+                _ => false,
+            };
+            let is_left_linear = match non_term_positions.as_slice() {
+                [] => true,
+                [position] => *position == 0,
+                _ => false,
+            };
+
+            if !is_right_linear && !is_left_linear {
+                return ClassVerdict {
+                    class: GrammarClass::Regular,
+                    member: Some(false),
+                    reason: VerdictReason::NotLinear {
+                        production: prod_index,
+                    },
+                };
+            }
+            if is_right_linear && !is_left_linear {
+                right_linear_example.get_or_insert(prod_index);
+            }
+            if is_left_linear && !is_right_linear {
+                left_linear_example.get_or_insert(prod_index);
+            }
+        }
+
+        match (right_linear_example, left_linear_example) {
+            (Some(right), Some(left)) => ClassVerdict {
+                class: GrammarClass::Regular,
+                member: Some(false),
+                reason: VerdictReason::MixedLinearity {
+                    right_linear: right,
+                    left_linear: left,
+                },
+            },
+            (None, Some(_)) => ClassVerdict {
+                class: GrammarClass::Regular,
+                member: Some(true),
+                reason: VerdictReason::LeftLinear,
+            },
+            _ => ClassVerdict {
+                class: GrammarClass::Regular,
+                member: Some(true),
+                reason: VerdictReason::RightLinear,
+            },
+        }
+    }
+
+    fn ll1_verdict(
+        &self,
+        ll1_parsing_table: &HashMap<char, HashMap<char, Vec<usize>>>,
+    ) -> ClassVerdict {
+        let mut sorted_terms = self.get_sorted_terms();
+        sorted_terms.push('$');
+
+        let mut conflicts = vec![];
+        for non_term in self.get_sorted_non_terms() {
+            let row = ll1_parsing_table.get(&non_term).unwrap();
+            for term in sorted_terms.iter() {
+                let prod_indices = row.get(term).unwrap();
+                if prod_indices.len() > 1 {
+                    conflicts.push(ConflictCell::Ll1 {
+                        non_term,
+                        term: *term,
+                        productions: prod_indices.clone(),
+                    });
+                }
+            }
+        }
+
+        Self::conflicts_verdict(GrammarClass::Ll1, conflicts)
+    }
+
+    fn lr_verdict(
+        &self,
+        class: GrammarClass,
+        parsing_table: &[HashMap<char, Vec<Action>>],
+    ) -> ClassVerdict {
+        let sorted_symbols = [
+            self.get_sorted_terms(),
+            vec!['$'],
+            self.get_sorted_non_terms(),
+        ]
+        .concat();
+
+        let mut conflicts = vec![];
+        for (node_index, row) in parsing_table.iter().enumerate() {
+            for symbol in sorted_symbols.iter() {
+                if let Some(actions) = row.get(symbol)
+                    && actions.len() > 1
+                {
+                    conflicts.push(ConflictCell::Lr {
+                        state: node_index,
+                        symbol: *symbol,
+                        actions: actions.clone(),
+                    });
+                }
+            }
+        }
+
+        Self::conflicts_verdict(class, conflicts)
+    }
+
+    fn conflicts_verdict(class: GrammarClass, conflicts: Vec<ConflictCell>) -> ClassVerdict {
+        if conflicts.is_empty() {
+            return ClassVerdict {
+                class,
+                member: Some(true),
+                reason: VerdictReason::NoConflicts,
+            };
+        }
+
+        ClassVerdict {
+            class,
+            member: Some(false),
+            reason: VerdictReason::Conflicts { cells: conflicts },
+        }
+    }
+
+    /// Looks for a sentence of at most `max_len` terminals having two distinct leftmost
+    /// derivations. Sentential forms are explored breadth first: since every form is expanded
+    /// once, reaching an already seen form means it has two different leftmost derivations.
+    pub fn find_ambiguity_witness(&self, max_len: usize) -> Option<AmbiguityWitness> {
+        let start = self.starting_prod.as_ref()?.body.clone();
+        let shortest_yields = self.get_shortest_yields();
+
+        let min_yield_len = |form: &[char]| -> Option<usize> {
+            form.iter().try_fold(0, |acc, symbol| {
+                if self.non_terms.contains(symbol) {
+                    shortest_yields.get(symbol).map(|y| acc + y.0.len())
+                } else {
+                    Some(acc + 1)
+                }
+            })
+        };
+
+        // For each explored form, the last derivation step that produced it
+        let mut parents: HashMap<Vec<char>, Option<(Vec<char>, usize)>> = HashMap::new();
+        parents.insert(start.clone(), None);
+        let mut queue = VecDeque::from([start]);
+        let mut explored = 0;
+
+        while let Some(form) = queue.pop_front() {
+            explored += 1;
+            if explored > AMBIGUITY_MAX_EXPLORED_FORMS {
+                break;
+            }
+
+            let Some(position) = form
+                .iter()
+                .position(|symbol| self.non_terms.contains(symbol))
+            else {
+                continue;
+            };
+
+            for (prod_index, production) in self.productions.iter().enumerate() {
+                if production.driver != form[position] {
+                    continue;
+                }
+
+                let new_form = [
+                    &form[..position],
+                    production.body.as_slice(),
+                    &form[position + 1..],
+                ]
+                .concat();
+
+                match min_yield_len(&new_form) {
+                    Some(len) if len <= max_len && new_form.len() <= 2 * max_len + 1 => {}
+                    _ => continue,
+                }
+
+                if parents.contains_key(&new_form) {
+                    let mut first = self.get_derivation(&parents, &new_form);
+                    let mut second = self.get_derivation(&parents, &form);
+                    second.push(prod_index);
+
+                    let completion = self.complete_leftmost(&new_form, &shortest_yields);
+                    let sentence = completion.0;
+                    first.extend(completion.1.iter());
+                    second.extend(completion.1.iter());
+
+                    return Some(AmbiguityWitness {
+                        sentence,
+                        sentential_form: new_form,
+                        derivations: (first, second),
+                    });
+                }
+
+                parents.insert(new_form.clone(), Some((form.clone(), prod_index)));
+                queue.push_back(new_form);
+            }
+        }
+
+        None
+    }
+
+    fn get_derivation(
+        &self,
+        parents: &HashMap<Vec<char>, Option<(Vec<char>, usize)>>,
+        form: &[char],
+    ) -> Vec<usize> {
+        let mut derivation = vec![];
+        let mut current = form.to_vec();
+        while let Some(Some((parent, prod_index))) = parents.get(&current) {
+            derivation.push(*prod_index);
+            current = parent.clone();
+        }
+        derivation.reverse();
+        derivation
+    }
+
+    /// Derives a terminal string from `form` expanding every non terminal with its shortest
+    /// yield, returning the string and the production indices of the leftmost derivation.
+    fn complete_leftmost(
+        &self,
+        form: &[char],
+        shortest_yields: &HashMap<char, (Vec<char>, Vec<usize>)>,
+    ) -> (Vec<char>, Vec<usize>) {
+        let mut sentence = vec![];
+        let mut derivation = vec![];
+        for symbol in form {
+            match shortest_yields.get(symbol) {
+                Some((yield_str, yield_derivation)) if self.non_terms.contains(symbol) => {
+                    sentence.extend(yield_str.iter());
+                    derivation.extend(yield_derivation.iter());
+                }
+                _ => sentence.push(*symbol),
+            }
+        }
+        (sentence, derivation)
+    }
+
+    /// Returns, for each productive non terminal, its shortest terminal yield together with the
+    /// production indices of a leftmost derivation of it.
+    pub(super) fn get_shortest_yields(&self) -> HashMap<char, (Vec<char>, Vec<usize>)> {
+        let mut shortest_yields: HashMap<char, (Vec<char>, Vec<usize>)> = HashMap::new();
+        let mut changed = true;
+
+        while changed {
+            changed = false;
+            for (prod_index, production) in self.productions.iter().enumerate() {
+                let mut candidate = (vec![], vec![prod_index]);
+                let mut productive = true;
+                for symbol in production.body.iter() {
+                    if self.non_terms.contains(symbol) {
+                        match shortest_yields.get(symbol) {
+                            Some((yield_str, derivation)) => {
+                                candidate.0.extend(yield_str.iter());
+                                candidate.1.extend(derivation.iter());
+                            }
+                            None => {
+                                productive = false;
+                                break;
+                            }
+                        }
+                    } else {
+                        candidate.0.push(*symbol);
+                    }
+                }
+
+                let improves = match shortest_yields.get(&production.driver) {
+                    Some(current) => candidate.0.len() < current.0.len(),
+                    None => true,
+                };
+                if productive && improves {
+                    shortest_yields.insert(production.driver, candidate);
+                    changed = true;
+                }
+            }
+        }
+
+        shortest_yields
+    }
+
+    /// Formats a terminal string, using `ε` for the empty one
+    pub fn format_sentence(&self, sentence: &[char]) -> String {
+        if sentence.is_empty() {
+            String::from("ε")
+        } else {
+            self.format_symbols(sentence)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::args::error::{GrammarDecodeError, SyntaxErrorKind};
+    use crate::grammar::{
+        create_grammar::create_grammar_from_str,
+        parse_structs::{ParsingTable, ParsingTableKind},
+    };
+
+    /// LALR(1) but not SLR(1): `=` is in FOLLOW(R), but not in the lookaheads of `R -> L•`
+    const LALR1_NOT_SLR1: &str = "S -> L = R | R . L -> * R | i . R -> L .";
+    /// LR(1) but not LALR(1): merging the states reducing `c` on `d` and on `e` makes them clash
+    const LR1_NOT_LALR1: &str = "S -> a A d | b B d | a B e | b A e . A -> c . B -> c .";
+
+    /// The cells of the LR table of the given kind holding more than one action
+    fn conflicting_cells(grammar: &Grammar, kind: ParsingTableKind) -> Vec<(usize, char)> {
+        let ParsingTable::Lr { rows, .. } = grammar.get_parsing_table(kind, None) else {
+            panic!("{} is not an LR table", kind);
+        };
+        let mut cells: Vec<(usize, char)> = rows
+            .iter()
+            .enumerate()
+            .flat_map(|(state, row)| {
+                row.iter()
+                    .filter(|(_, actions)| actions.len() > 1)
+                    .map(move |(symbol, _)| (state, *symbol))
+            })
+            .collect();
+        cells.sort();
+        cells
+    }
+
+    fn verdict(classification: &GrammarClassification, class: GrammarClass) -> &ClassVerdict {
+        classification
+            .verdicts
+            .iter()
+            .find(|verdict| verdict.class == class)
+            .unwrap()
+    }
+
+    #[test]
+    fn lalr1_grammar_which_is_not_slr1() {
+        let grammar = create_grammar_from_str(LALR1_NOT_SLR1).unwrap();
+        assert_eq!(
+            conflicting_cells(&grammar, ParsingTableKind::Slr1),
+            vec![(4, '=')]
+        );
+        assert!(conflicting_cells(&grammar, ParsingTableKind::Lalr1).is_empty());
+        assert!(conflicting_cells(&grammar, ParsingTableKind::Lr1).is_empty());
+
+        let classification = grammar.classify();
+        assert_eq!(
+            verdict(&classification, GrammarClass::Slr1).reason,
+            VerdictReason::Conflicts {
+                cells: vec![ConflictCell::Lr {
+                    state: 4,
+                    symbol: '=',
+                    actions: vec![Action::Shift(8), Action::Reduce(4)],
+                }]
+            }
+        );
+        assert_eq!(classification.is(GrammarClass::Slr1), Some(false));
+        assert_eq!(classification.is(GrammarClass::Lalr1), Some(true));
+        assert_eq!(classification.is(GrammarClass::Lr1), Some(true));
+        assert_eq!(
+            verdict(&classification, GrammarClass::Ambiguous).reason,
+            VerdictReason::UnambiguousClass {
+                class: GrammarClass::Lalr1
+            }
+        );
+    }
+
+    #[test]
+    fn lr1_grammar_which_is_not_lalr1() {
+        let grammar = create_grammar_from_str(LR1_NOT_LALR1).unwrap();
+        assert_eq!(
+            conflicting_cells(&grammar, ParsingTableKind::Lalr1),
+            vec![(6, 'd'), (6, 'e')]
+        );
+        assert!(conflicting_cells(&grammar, ParsingTableKind::Lr1).is_empty());
+
+        let classification = grammar.classify();
+        assert_eq!(
+            verdict(&classification, GrammarClass::Lalr1).reason,
+            VerdictReason::Conflicts {
+                cells: vec![
+                    ConflictCell::Lr {
+                        state: 6,
+                        symbol: 'd',
+                        actions: vec![Action::Reduce(4), Action::Reduce(5)],
+                    },
+                    ConflictCell::Lr {
+                        state: 6,
+                        symbol: 'e',
+                        actions: vec![Action::Reduce(4), Action::Reduce(5)],
+                    },
+                ]
+            }
+        );
+        assert_eq!(classification.is(GrammarClass::Lalr1), Some(false));
+        assert_eq!(classification.is(GrammarClass::Lr1), Some(true));
+        assert_eq!(classification.is(GrammarClass::Ambiguous), Some(false));
+    }
+
+    /// Applies the productions of a leftmost derivation to the start symbol
+    fn derive_leftmost(grammar: &Grammar, derivation: &[usize]) -> Vec<char> {
+        let mut form = vec![grammar.get_start_symbol().unwrap()];
+        for prod_index in derivation {
+            let production = &grammar.productions[*prod_index];
+            let position = form
+                .iter()
+                .position(|symbol| grammar.non_terms.contains(symbol))
+                .unwrap();
+            assert_eq!(form[position], production.driver);
+            form.splice(position..=position, production.body.iter().copied());
+        }
+        form
+    }
+
+    #[test]
+    fn ambiguity_witness_has_two_leftmost_derivations() {
+        let grammar = create_grammar_from_str("E -> E a E | b .").unwrap();
+        let witness = grammar.find_ambiguity_witness(5).unwrap();
+
+        assert_eq!(witness.sentence, vec!['b', 'a', 'b', 'a', 'b']);
+        assert_ne!(witness.derivations.0, witness.derivations.1);
+        assert_eq!(
+            derive_leftmost(&grammar, &witness.derivations.0),
+            witness.sentence
+        );
+        assert_eq!(
+            derive_leftmost(&grammar, &witness.derivations.1),
+            witness.sentence
+        );
+        assert_eq!(grammar.classify().is(GrammarClass::Ambiguous), Some(true));
+    }
+
+    #[test]
+    fn no_ambiguity_witness_for_unambiguous_grammars() {
+        let grammar = create_grammar_from_str("S -> a S b | .").unwrap();
+        assert!(grammar.find_ambiguity_witness(8).is_none());
+    }
+
+    #[test]
+    fn no_ambiguity_witness_beyond_the_maximum_length() {
+        let grammar = create_grammar_from_str("E -> E a E | b .").unwrap();
+        assert!(grammar.find_ambiguity_witness(4).is_none());
+    }
+
+    #[test]
+    fn non_productive_grammars_are_rejected_before_building_the_lalr1_table() {
+        // The LR(1) closures leave out `A -> •B`, as FIRST(B) is empty, so some LR(1) states have
+        // no LR(0) state with the same core to be merged into
+        let errors = match create_grammar_from_str("S -> A .\nA -> B .\nB -> A B .\n") {
+            Err(GrammarDecodeError::InvalidFormat(errors)) => errors,
+            result => panic!(
+                "expected the non productive non terminals, got {:?}",
+                result
+            ),
+        };
+        let kinds: Vec<SyntaxErrorKind> = errors.into_iter().map(|error| error.kind).collect();
+        assert_eq!(
+            kinds,
+            ["S", "A", "B"].map(|symbol| SyntaxErrorKind::NonProductive {
+                symbol: symbol.to_string()
+            })
+        );
+
+        let grammar = create_grammar_from_str("S -> A .\nA -> B .\nB -> A B | b .\n").unwrap();
+        assert_eq!(
+            grammar.get_lalr1_parsing_table(None, None).len(),
+            grammar.get_lr0_parsing_table().len()
+        );
+        assert_eq!(grammar.classify().is(GrammarClass::Lalr1), Some(false));
+    }
+}
@@ -1,9 +1,19 @@
-use std::fs;
+use std::{
+    collections::HashSet,
+    fs,
+    io::{self, Read},
+};
 
 use crate::{
-    args::{Args, error::GrammarDecodeError},
-    grammar::parse_structs::Production,
-    util::truncate_after_last,
+    args::{
+        error::{GrammarDecodeError, SyntaxError, SyntaxErrorKind},
+        finalized::InputType,
+    },
+    grammar::{
+        lexer::{Token, TokenKind, tokenize},
+        parse_structs::Production,
+        precedence::{Associativity, Precedence},
+    },
 };
 use base64::Engine as _;
 
@@ -15,6 +25,14 @@ pub fn read_from_file(file_path: String) -> Result<String, GrammarDecodeError> {
     })
 }
 
+pub fn read_from_stdin() -> Result<String, GrammarDecodeError> {
+    let mut text = String::new();
+    io::stdin().read_to_string(&mut text).map_err(|err| {
+        GrammarDecodeError::ParseError(format!("Failed to read from stdin: {}", err))
+    })?;
+    Ok(text)
+}
+
 pub fn decode_base_64(base64: String) -> Result<String, GrammarDecodeError> {
     let decoded_bytes = base64::engine::general_purpose::STANDARD
         .decode(base64)
@@ -27,73 +45,700 @@ pub fn decode_base_64(base64: String) -> Result<String, GrammarDecodeError> {
     })
 }
 
-pub fn decode_grammar(args: &Args) -> Result<Grammar, GrammarDecodeError> {
-    let decoded_text = match (&args.file, &args.base64) {
-        (Some(file_path), None) => read_from_file(file_path.clone()),
-        (None, Some(base64)) => decode_base_64(base64.clone()),
-        (None, None) => panic!("This should not be happening, clap should have handled this"),
-        (Some(_), Some(_)) => {
-            panic!("This should not be happening, clap should have handled this")
-        }
+pub fn decode_grammar(input_type: &InputType) -> Result<Grammar, GrammarDecodeError> {
+    let decoded_text = match input_type {
+        InputType::File(file_path) => read_from_file(file_path.clone()),
+        InputType::Base64(base64) => decode_base_64(base64.clone()),
+        InputType::Stdin => read_from_stdin(),
     }?;
 
     create_grammar_from_str(&decoded_text)
 }
 
-pub fn create_grammar_from_str(grammar_str: &String) -> Result<Grammar, GrammarDecodeError> {
-    // println!("Creating grammar from string:\n{}", grammar_str);
-
-    let cleaned_str = truncate_after_last(grammar_str.as_str(), '.');
-    let mut grammar = Grammar::new();
-    for line in cleaned_str.split('\n') {
-        let mut cleaned_line = line.trim();
-        if cleaned_line.is_empty() {
-            continue;
-        }
-        cleaned_line = cleaned_line.trim_end_matches('.');
-
-        let arrow_pos = cleaned_line
-            .find("->")
-            .ok_or_else(|| GrammarDecodeError::InvalidFormat(String::from("No -> arrow")))?;
-
-        let (mut driver_str, mut prod_bodies_str) = cleaned_line.split_at(arrow_pos);
-
-        driver_str = driver_str.trim();
-        prod_bodies_str = &prod_bodies_str[2..];
-        prod_bodies_str = prod_bodies_str.trim();
-
-        if driver_str.len() != 1 {
-            return Err(GrammarDecodeError::InvalidFormat(format!(
-                "Grammar is not free: expected one symbol on the left side of '->', found {:?}",
-                driver_str
-            )));
-        }
-
-        for body_str in prod_bodies_str.split('|') {
-            let body_str = body_str.trim();
-            let mut body = vec![];
-            for symbol in body_str.split(' ') {
-                let symbol_len = symbol.len();
-                if symbol_len == 1 {
-                    body.push(symbol.chars().next().unwrap());
-                } else if symbol_len == 0 {
-                    // body.push(' ');
-                } else {
-                    return Err(GrammarDecodeError::InvalidFormat(format!(
-                        "Each symbol should be a single character: {:?}",
-                        symbol
-                    )));
+/// Builds a grammar from its textual definition. The format is the one used by grammophone,
+/// i.e. `A -> a B | .` rules terminated by a dot, extended with:
+/// - comments starting with `#` or `//`;
+/// - the `ε` or `epsilon` keyword for empty bodies;
+/// - quoted terminals such as `'+'` or `"if"`;
+/// - alternatives spanning multiple lines;
+/// - `%start S`, `%token if then else` and `%left`/`%right`/`%nonassoc`/`%prec` directives;
+/// - after a `%ebnf` directive, `[ x ]`, `{ x }`, `x*`, `x+` and `( x | y )` constructs, which
+///   are desugared into plain productions over generated helper non terminals.
+///
+/// Parsing goes on after an error, skipping to the next rule or directive, so that every error
+/// in the definition is reported.
+pub fn create_grammar_from_str(grammar_str: &str) -> Result<Grammar, GrammarDecodeError> {
+    let (tokens, mut errors) = tokenize(grammar_str);
+    let mut parser = GrammarParser {
+        tokens: &tokens,
+        position: 0,
+        grammar: Grammar::new(),
+        start_symbol: None,
+        ebnf: false,
+        helper_rules: vec![],
+        body_symbols: vec![],
+        drivers: vec![],
+    };
+
+    while let Some(token) = parser.peek() {
+        let start = parser.position;
+        let result = match &token.kind {
+            TokenKind::Directive(directive) => parser.parse_directive(directive),
+            TokenKind::Symbol(_) => parser.parse_rule(),
+            _ => Err(error_at(
+                token,
+                SyntaxErrorKind::UnexpectedToken {
+                    expected: String::from("a production or a directive"),
+                    found: describe_token(token),
+                },
+            )),
+        };
+        if let Err(error) = result {
+            errors.push(error);
+            parser.recover(start);
+        }
+    }
+
+    if let Some((start_symbol, token)) = parser.start_symbol {
+        if parser
+            .grammar
+            .productions
+            .iter()
+            .any(|prod| prod.driver == start_symbol)
+        {
+            parser.grammar.set_start_symbol(start_symbol);
+        } else {
+            errors.push(error_at(
+                token,
+                SyntaxErrorKind::UndefinedStart {
+                    symbol: token.text.clone(),
+                },
+            ));
+        }
+    }
+
+    let drivers: HashSet<char> = parser
+        .grammar
+        .productions
+        .iter()
+        .map(|prod| prod.driver)
+        .collect();
+    let mut undefined_non_terms = HashSet::new();
+    for (symbol, token) in parser.body_symbols.iter() {
+        let is_non_term = symbol.is_uppercase() || parser.grammar.non_terms.contains(symbol);
+        // Each undefined non terminal is only reported where it is first used
+        if is_non_term && !drivers.contains(symbol) && undefined_non_terms.insert(*symbol) {
+            errors.push(error_at(
+                token,
+                SyntaxErrorKind::UndefinedNonTerminal {
+                    symbol: token.text.clone(),
+                },
+            ));
+        }
+    }
+
+    // Non productive non terminals make the LR(1) closures miss the items of the LR(0) ones, so
+    // they are rejected. They are only looked for in otherwise valid grammars, as undefined non
+    // terminals are non productive as well.
+    if errors.is_empty() {
+        let productive_non_terms = parser.grammar.get_shortest_yields();
+        let mut non_productive_non_terms = HashSet::new();
+        for (driver, token) in parser.drivers.iter() {
+            if !productive_non_terms.contains_key(driver)
+                && non_productive_non_terms.insert(*driver)
+            {
+                errors.push(error_at(
+                    token,
+                    SyntaxErrorKind::NonProductive {
+                        symbol: token.text.clone(),
+                    },
+                ));
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        if parser.grammar.productions.is_empty() {
+            return Err(GrammarDecodeError::EmptyGrammar);
+        }
+        return Ok(parser.grammar);
+    }
+
+    let lines: Vec<&str> = grammar_str.lines().collect();
+    errors.sort_by_key(|error| (error.line, error.column));
+    for error in errors.iter_mut() {
+        if let Some(line) = lines.get(error.line - 1) {
+            error.source_line = line.to_string();
+        }
+    }
+    Err(GrammarDecodeError::InvalidFormat(errors))
+}
+
+/// Builds an error located at the given token. The source line is filled in by
+/// `create_grammar_from_str` once the whole definition has been parsed.
+fn error_at(token: &Token, kind: SyntaxErrorKind) -> SyntaxError {
+    SyntaxError {
+        kind,
+        line: token.line,
+        column: token.column,
+        text: token.text.clone(),
+        source_line: String::new(),
+    }
+}
+
+/// Placeholder for the helper non terminal inside the bodies passed to `add_helper_rule`
+const SELF: char = '\0';
+
+struct GrammarParser<'t> {
+    tokens: &'t [Token],
+    position: usize,
+    grammar: Grammar,
+    /// Symbol given to `%start`, together with its token
+    start_symbol: Option<(char, &'t Token)>,
+    /// Whether brackets and postfix operators are EBNF constructs, enabled by `%ebnf`
+    ebnf: bool,
+    /// Rules of the helper non terminals generated by the EBNF constructs of the current rule
+    helper_rules: Vec<(char, Vec<Vec<char>>)>,
+    /// Symbols found in production bodies together with their token, used to report undefined
+    /// non terminals
+    body_symbols: Vec<(char, &'t Token)>,
+    /// Drivers of the rules together with their token, used to report non productive non
+    /// terminals
+    drivers: Vec<(char, &'t Token)>,
+}
+
+impl<'t> GrammarParser<'t> {
+    fn peek(&self) -> Option<&'t Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn peek_kind(&self, offset: usize) -> Option<&'t TokenKind> {
+        self.tokens
+            .get(self.position + offset)
+            .map(|token| &token.kind)
+    }
+
+    fn next(&mut self) -> Option<&'t Token> {
+        let token = self.tokens.get(self.position);
+        self.position += 1;
+        token
+    }
+
+    /// Parses `A -> body | body | ... .`. The final dot can be omitted when the rule is followed
+    /// by another rule or by a directive, as the line based format used to allow.
+    fn parse_rule(&mut self) -> Result<(), SyntaxError> {
+        let driver_token = self.next().unwrap();
+        let driver = match &driver_token.kind {
+            TokenKind::Symbol(name) => self.parse_non_term(driver_token, name)?,
+            _ => unreachable!("rules are only parsed starting from a symbol"),
+        };
+
+        if self.peek_kind(0) != Some(&TokenKind::Arrow) {
+            let driver_symbols: Vec<&str> = self.tokens[self.position - 1..]
+                .iter()
+                .map_while(|token| match &token.kind {
+                    TokenKind::Symbol(_) | TokenKind::Quoted(_) => Some(token.text.as_str()),
+                    _ => None,
+                })
+                .collect();
+            let followed_by_arrow = self
+                .peek_kind(driver_symbols.len() - 1)
+                .is_some_and(|kind| *kind == TokenKind::Arrow);
+            return Err(if followed_by_arrow {
+                SyntaxError {
+                    text: driver_symbols.join(" "),
+                    ..error_at(
+                        driver_token,
+                        SyntaxErrorKind::NotFree {
+                            driver: driver_symbols.join(" "),
+                        },
+                    )
                 }
+            } else {
+                error_at(
+                    self.peek().unwrap_or(driver_token),
+                    SyntaxErrorKind::MissingArrow,
+                )
+            });
+        }
+        self.position += 1;
+        self.drivers.push((driver, driver_token));
+
+        loop {
+            let (body, precedence) = self.parse_sequence(driver, None)?;
+            self.add_production(driver, body, precedence);
+            match self.peek_kind(0) {
+                Some(TokenKind::Pipe) => self.position += 1,
+                Some(TokenKind::Dot) => {
+                    self.position += 1;
+                    break;
+                }
+                _ => break,
             }
-            let var_name = Production {
-                index: Some(grammar.productions.len()),
-                driver: driver_str.chars().next().unwrap(),
-                body,
+        }
+
+        // Rules of the helper non terminals introduced by the EBNF desugaring follow the rule
+        // they were generated from
+        for (helper, bodies) in std::mem::take(&mut self.helper_rules) {
+            for body in bodies {
+                self.add_production(helper, body, None);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Parses the symbols of a single alternative, stopping before the `|`, the closing bracket
+    /// (when inside an EBNF group) or the end of the rule. Returns the body together with the
+    /// `%prec` terminal, if any.
+    ///
+    /// `closing` is the bracket closing the enclosing group together with its opening token.
+    fn parse_sequence(
+        &mut self,
+        driver: char,
+        closing: Option<(char, &'t Token)>,
+    ) -> Result<(Vec<char>, Option<char>), SyntaxError> {
+        let mut items: Vec<Vec<char>> = vec![];
+        let mut precedence = None;
+
+        while let Some(token) = self.peek() {
+            let at_end = match &token.kind {
+                TokenKind::Pipe => true,
+                TokenKind::Close(c) if self.ebnf => match closing {
+                    Some((expected, _)) if expected == *c => true,
+                    _ => {
+                        return Err(error_at(
+                            token,
+                            SyntaxErrorKind::UnexpectedToken {
+                                expected: closing
+                                    .map_or(String::from("a symbol"), |(c, _)| format!("`{}`", c)),
+                                found: describe_token(token),
+                            },
+                        ));
+                    }
+                },
+                TokenKind::Dot => true,
+                TokenKind::Directive(directive) => directive != "prec",
+                _ => self.is_rule_start(),
             };
-            let production = var_name;
-            grammar.add_production(production);
+            if at_end {
+                break;
+            }
+            self.position += 1;
+
+            match &token.kind {
+                TokenKind::Directive(directive) => {
+                    if closing.is_some() {
+                        return Err(error_at(token, SyntaxErrorKind::PrecInsideGroup));
+                    }
+                    let missing_terminal = || {
+                        error_at(
+                            token,
+                            SyntaxErrorKind::MissingTerminal {
+                                directive: directive.clone(),
+                            },
+                        )
+                    };
+                    let prec_token = self.next().ok_or_else(missing_terminal)?;
+                    precedence = Some(
+                        self.parse_body_symbol(prec_token)?
+                            .ok_or_else(missing_terminal)?,
+                    );
+                }
+                TokenKind::Open(_) if self.ebnf => {
+                    let item = self.parse_group(driver, token)?;
+                    items.push(item);
+                }
+                TokenKind::Postfix(operator) if self.ebnf => {
+                    let item = items.pop().unwrap_or_default();
+                    let bodies = match operator {
+                        // N -> item N | ε
+                        '*' => vec![[item.as_slice(), &[SELF]].concat(), vec![]],
+                        // N -> item N | item
+                        _ => vec![[item.as_slice(), &[SELF]].concat(), item],
+                    };
+                    items.push(vec![self.add_helper_rule(driver, bodies)]);
+                }
+                _ => {
+                    let symbol = self.parse_body_symbol(token)?;
+                    if let Some(symbol) = symbol {
+                        self.body_symbols.push((symbol, token));
+                    }
+                    items.push(symbol.into_iter().collect());
+                }
+            }
+        }
+
+        if let Some((expected, open_token)) = closing
+            && !matches!(
+                self.peek_kind(0),
+                Some(TokenKind::Pipe | TokenKind::Close(_))
+            )
+        {
+            return Err(error_at(
+                open_token,
+                SyntaxErrorKind::UnclosedGroup { expected },
+            ));
         }
+
+        Ok((items.concat(), precedence))
     }
 
-    Ok(grammar)
+    /// Parses an EBNF group, the opening bracket being already consumed, and returns the
+    /// symbols replacing it in the enclosing body:
+    /// - `( a | b )` becomes `N` with `N -> a | b`, or is inlined when it has one alternative;
+    /// - `[ a ]` becomes `N` with `N -> a | ε`;
+    /// - `{ a }` becomes `N` with `N -> a N | ε`.
+    fn parse_group(
+        &mut self,
+        driver: char,
+        open_token: &'t Token,
+    ) -> Result<Vec<char>, SyntaxError> {
+        let open = match open_token.kind {
+            TokenKind::Open(open) => open,
+            _ => unreachable!("groups are only parsed starting from an opening bracket"),
+        };
+        let close = match open {
+            '(' => ')',
+            '[' => ']',
+            _ => '}',
+        };
+
+        let mut alternatives = vec![];
+        loop {
+            let (body, _) = self.parse_sequence(driver, Some((close, open_token)))?;
+            alternatives.push(body);
+            if self.next().map(|token| &token.kind) != Some(&TokenKind::Pipe) {
+                break;
+            }
+        }
+        if alternatives.iter().all(Vec::is_empty) {
+            return Err(error_at(open_token, SyntaxErrorKind::EmptyGroup));
+        }
+
+        let bodies = match open {
+            '(' if alternatives.len() == 1 => return Ok(alternatives.pop().unwrap()),
+            '(' => alternatives,
+            '[' => {
+                alternatives.push(vec![]);
+                alternatives
+            }
+            _ => {
+                let mut bodies: Vec<Vec<char>> = alternatives
+                    .into_iter()
+                    .map(|body| [body.as_slice(), &[SELF]].concat())
+                    .collect();
+                bodies.push(vec![]);
+                bodies
+            }
+        };
+
+        Ok(vec![self.add_helper_rule(driver, bodies)])
+    }
+
+    /// Creates a helper non terminal named after `driver` (e.g. `A_1`) having the given bodies,
+    /// where `SELF` stands for the helper itself. Repeated bodies, e.g. the two ε of `[ a | ]`,
+    /// are kept once.
+    fn add_helper_rule(&mut self, driver: char, bodies: Vec<Vec<char>>) -> char {
+        let helper = self.grammar.new_helper_non_term(driver);
+
+        let mut unique_bodies: Vec<Vec<char>> = vec![];
+        for body in bodies {
+            let body = body
+                .into_iter()
+                .map(|c| if c == SELF { helper } else { c })
+                .collect();
+            if !unique_bodies.contains(&body) {
+                unique_bodies.push(body);
+            }
+        }
+        self.helper_rules.push((helper, unique_bodies));
+        helper
+    }
+
+    /// Skips the rest of a rule or directive after an error, up to the next dot or to the next
+    /// line starting a rule or a directive. At least the token at `start` is skipped.
+    fn recover(&mut self, start: usize) {
+        self.helper_rules.clear();
+        self.position = self.position.max(start + 1);
+        while let Some(token) = self.peek() {
+            let new_line = self.tokens[self.position - 1].line != token.line;
+            if new_line && (matches!(token.kind, TokenKind::Directive(_)) || self.is_rule_start()) {
+                break;
+            }
+            self.position += 1;
+            if token.kind == TokenKind::Dot {
+                break;
+            }
+        }
+    }
+
+    /// Whether the next token starts a new line containing a `->` arrow, meaning that the
+    /// current rule was not terminated by a dot
+    fn is_rule_start(&self) -> bool {
+        let Some(token) = self.peek() else {
+            return false;
+        };
+        if self.position > 0 && self.tokens[self.position - 1].line == token.line {
+            return false;
+        }
+        self.tokens[self.position..]
+            .iter()
+            .take_while(|t| t.line == token.line)
+            .map_while(|t| match t.kind {
+                TokenKind::Pipe | TokenKind::Dot => None,
+                _ => Some(&t.kind),
+            })
+            .any(|kind| *kind == TokenKind::Arrow)
+    }
+
+    fn add_production(&mut self, driver: char, body: Vec<char>, precedence: Option<char>) {
+        let production = Production {
+            index: Some(self.grammar.productions.len()),
+            driver,
+            body,
+            precedence,
+        };
+        self.grammar.add_production(production);
+    }
+
+    /// Returns the symbol represented by a token inside a production body, `None` for the
+    /// epsilon keyword. The symbols reserved for the end of input and the augmented start are
+    /// rejected, as they would collide with the ones added by the parsing algorithms.
+    fn parse_body_symbol(&mut self, token: &Token) -> Result<Option<char>, SyntaxError> {
+        let symbol = self.parse_symbol(token)?;
+        match symbol {
+            Some(symbol @ ('$' | '@')) => Err(error_at(
+                token,
+                SyntaxErrorKind::ReservedSymbol {
+                    symbol: symbol.to_string(),
+                },
+            )),
+            _ => Ok(symbol),
+        }
+    }
+
+    /// Returns the symbol represented by a token, `None` for the epsilon keyword
+    fn parse_symbol(&mut self, token: &Token) -> Result<Option<char>, SyntaxError> {
+        match &token.kind {
+            TokenKind::Symbol(name) if name == "ε" || name == "epsilon" => Ok(None),
+            TokenKind::Symbol(name) => match self.grammar.get_symbol_by_name(name) {
+                Some(symbol) => Ok(Some(symbol)),
+                None => self.parse_non_term(token, name).map(Some),
+            },
+            TokenKind::Quoted(name) => Ok(Some(self.grammar.intern_term(name))),
+            // Outside of EBNF mode brackets and postfix operators are plain terminals
+            TokenKind::Open(c) | TokenKind::Close(c) | TokenKind::Postfix(c) => {
+                Ok(Some(self.grammar.intern_term(&c.to_string())))
+            }
+            _ => Err(error_at(
+                token,
+                SyntaxErrorKind::UnexpectedToken {
+                    expected: String::from("a symbol"),
+                    found: describe_token(token),
+                },
+            )),
+        }
+    }
+
+    /// Parses a symbol which is not a declared terminal. Besides single characters, names
+    /// starting with an upper case letter (e.g. `Expr` or `A_1`) are accepted as non terminals.
+    fn parse_non_term(&mut self, token: &Token, name: &str) -> Result<char, SyntaxError> {
+        if name.chars().count() > 1 && name.starts_with(|c: char| c.is_uppercase()) {
+            Ok(self.grammar.intern_non_term(name))
+        } else {
+            parse_single_char_symbol(token, name)
+        }
+    }
+
+    /// Parses a directive together with the symbols following it on the same line
+    fn parse_directive(&mut self, directive: &str) -> Result<(), SyntaxError> {
+        let directive_token = self.next().unwrap();
+        let line = directive_token.line;
+        let mut args = vec![];
+        while let Some(token) = self.peek() {
+            if token.line != line {
+                break;
+            }
+            match token.kind {
+                // A `*` or `+` following another argument is lexed as a postfix operator
+                TokenKind::Symbol(_) | TokenKind::Quoted(_) | TokenKind::Postfix(_) => {
+                    args.push(token)
+                }
+                TokenKind::Dot => {}
+                _ => break,
+            }
+            self.position += 1;
+        }
+
+        match directive {
+            "left" | "right" | "nonassoc" => {
+                let associativity = match directive {
+                    "left" => Associativity::Left,
+                    "right" => Associativity::Right,
+                    _ => Associativity::NonAssoc,
+                };
+                let level = self
+                    .grammar
+                    .precedences
+                    .values()
+                    .map(|precedence| precedence.level)
+                    .max()
+                    .unwrap_or(0)
+                    + 1;
+
+                for arg in args {
+                    let term = self.parse_body_symbol(arg)?.ok_or_else(|| {
+                        error_at(
+                            arg,
+                            SyntaxErrorKind::MissingTerminal {
+                                directive: directive.to_string(),
+                            },
+                        )
+                    })?;
+                    self.grammar.precedences.insert(
+                        term,
+                        Precedence {
+                            level,
+                            associativity,
+                        },
+                    );
+                }
+            }
+            "token" => {
+                for arg in args {
+                    match &arg.kind {
+                        TokenKind::Symbol(name) | TokenKind::Quoted(name) => {
+                            if name == "$" || name == "@" {
+                                return Err(error_at(
+                                    arg,
+                                    SyntaxErrorKind::ReservedSymbol {
+                                        symbol: name.to_string(),
+                                    },
+                                ));
+                            }
+                            self.grammar.intern_term(name);
+                        }
+                        TokenKind::Postfix(c) => {
+                            self.grammar.intern_term(&c.to_string());
+                        }
+                        _ => unreachable!(),
+                    }
+                }
+            }
+            "ebnf" => self.ebnf = true,
+            "start" => match args.as_slice() {
+                [
+                    token @ Token {
+                        kind: TokenKind::Symbol(name),
+                        ..
+                    },
+                ] => self.start_symbol = Some((self.parse_non_term(token, name)?, token)),
+                _ => {
+                    return Err(error_at(directive_token, SyntaxErrorKind::InvalidStart));
+                }
+            },
+            _ => {
+                return Err(error_at(
+                    directive_token,
+                    SyntaxErrorKind::UnknownDirective {
+                        directive: directive.to_string(),
+                    },
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn describe_token(token: &Token) -> String {
+    match &token.kind {
+        TokenKind::Symbol(_) => format!("symbol `{}`", token.text),
+        TokenKind::Quoted(_) => format!("terminal `{}`", token.text),
+        TokenKind::Directive(_) => format!("directive `{}`", token.text),
+        _ => format!("`{}`", token.text),
+    }
+}
+
+fn parse_single_char_symbol(token: &Token, symbol: &str) -> Result<char, SyntaxError> {
+    let mut chars = symbol.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => Err(error_at(
+            token,
+            SyntaxErrorKind::MultiCharSymbol {
+                symbol: symbol.to_string(),
+            },
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn syntax_errors(definition: &str) -> Vec<SyntaxError> {
+        match create_grammar_from_str(definition) {
+            Err(GrammarDecodeError::InvalidFormat(errors)) => errors,
+            result => panic!("expected a syntax error, got {:?}", result.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn rejects_reserved_symbols() {
+        for (definition, symbol, column) in [
+            ("S -> a '$' b .", "$", 8),
+            ("S -> a $ b .", "$", 8),
+            ("S -> a \"@\" .", "@", 8),
+            ("%token $\nS -> a .", "$", 8),
+        ] {
+            let errors = syntax_errors(definition);
+            assert_eq!(errors.len(), 1, "{}", definition);
+            assert_eq!(
+                errors[0].kind,
+                SyntaxErrorKind::ReservedSymbol {
+                    symbol: symbol.to_string()
+                }
+            );
+            assert_eq!((errors[0].line, errors[0].column), (1, column));
+        }
+    }
+
+    fn desugar(definition: &str) -> String {
+        create_grammar_from_str(definition)
+            .unwrap()
+            .get_definition_string()
+    }
+
+    #[test]
+    fn desugars_spaced_postfix_operators() {
+        assert_eq!(
+            desugar("%ebnf\nS -> ( a | ) * b ."),
+            "S -> S_2 b .\nS_1 -> a | ε .\nS_2 -> S_1 S_2 | ε .\n"
+        );
+        assert_eq!(desugar("S -> a + b ."), "S -> a '+' b .\n");
+    }
+
+    #[test]
+    fn rejects_empty_groups() {
+        for definition in [
+            "%ebnf\nS -> [ ] a .",
+            "%ebnf\nS -> ( | ) a .",
+            "%ebnf\nS -> { } .",
+        ] {
+            let errors = syntax_errors(definition);
+            assert_eq!(errors.len(), 1, "{}", definition);
+            assert_eq!(errors[0].kind, SyntaxErrorKind::EmptyGroup);
+            assert_eq!((errors[0].line, errors[0].column), (2, 6));
+        }
+    }
+
+    #[test]
+    fn keeps_repeated_group_bodies_once() {
+        assert_eq!(
+            desugar("%ebnf\nS -> [ a | ] b ."),
+            "S -> S_1 b .\nS_1 -> a | ε .\n"
+        );
+    }
 }
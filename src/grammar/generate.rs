@@ -0,0 +1,81 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashSet},
+};
+
+use super::grammar::Grammar;
+
+/// Maximum number of sentential forms explored while generating sentences, so that generation
+/// terminates on grammars whose forms grow without producing new sentences (e.g. `A -> A A | ε`)
+const GENERATE_MAX_EXPLORED_FORMS: usize = 100_000;
+
+impl Grammar {
+    /// Returns up to `count` distinct sentences of the language made of at most `max_len`
+    /// terminals, shortest first. Sentential forms are expanded leftmost, in order of the length
+    /// of the shortest sentence they can derive, so that no shorter sentence is ever skipped.
+    pub fn generate_sentences(&self, count: usize, max_len: usize) -> Vec<Vec<char>> {
+        let Some(start) = self.get_start_symbol() else {
+            return vec![];
+        };
+        let shortest_yields = self.get_shortest_yields();
+
+        let min_yield_len = |form: &[char]| -> Option<usize> {
+            form.iter().try_fold(0, |acc, symbol| {
+                if self.non_terms.contains(symbol) {
+                    shortest_yields.get(symbol).map(|y| acc + y.0.len())
+                } else {
+                    Some(acc + 1)
+                }
+            })
+        };
+
+        let mut sentences = vec![];
+        let mut seen: HashSet<Vec<char>> = HashSet::new();
+        // Forms are ordered by the length of their shortest yield, then by discovery order
+        let mut queue = BinaryHeap::new();
+        let mut discovered = 0;
+        if min_yield_len(&[start]).is_some_and(|len| len <= max_len) {
+            queue.push(Reverse((0, discovered, vec![start])));
+        }
+
+        let mut explored = 0;
+        while let Some(Reverse((_, _, form))) = queue.pop() {
+            if sentences.len() >= count || explored >= GENERATE_MAX_EXPLORED_FORMS {
+                break;
+            }
+            explored += 1;
+
+            let Some(position) = form
+                .iter()
+                .position(|symbol| self.non_terms.contains(symbol))
+            else {
+                sentences.push(form);
+                continue;
+            };
+
+            for production in self
+                .productions
+                .iter()
+                .filter(|prod| prod.driver == form[position])
+            {
+                let new_form = [
+                    &form[..position],
+                    production.body.as_slice(),
+                    &form[position + 1..],
+                ]
+                .concat();
+
+                let len = match min_yield_len(&new_form) {
+                    Some(len) if len <= max_len && new_form.len() <= 2 * max_len + 1 => len,
+                    _ => continue,
+                };
+                if seen.insert(new_form.clone()) {
+                    discovered += 1;
+                    queue.push(Reverse((len, discovered, new_form)));
+                }
+            }
+        }
+
+        sentences
+    }
+}
@@ -1,25 +1,34 @@
-use std::cmp::max;
 use std::collections::{HashMap, HashSet};
 
-use petgraph::algo::{condensation, toposort};
-use petgraph::graph::NodeIndex;
-use petgraph::visit::EdgeRef;
-use petgraph::{Direction, Graph};
+use serde::Serialize;
 
-use crate::args::error::GrammarDecodeError;
-use crate::lr0::{Lr0Item, get_parsing_automaton};
-use crate::util::get_dot_from_petgraph;
-use std::collections::VecDeque;
+use crate::util::{serialize_sorted_map, serialize_sorted_set};
 
-use super::latex::LatexFormatOutputFormatDescriptor;
-use super::parse_structs::{Action, FirstFollowSet, Production};
+use super::parse_structs::Production;
+use super::precedence::{Associativity, Precedence};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct Grammar {
     pub starting_prod: Option<Production>,
     pub productions: Vec<Production>,
+    #[serde(serialize_with = "serialize_sorted_set")]
     pub terms: HashSet<char>,
+    #[serde(serialize_with = "serialize_sorted_set")]
     pub non_terms: HashSet<char>,
+    /// Precedence and associativity of terminals, declared with `%left`, `%right` and
+    /// `%nonassoc`
+    #[serde(serialize_with = "serialize_sorted_map")]
+    pub precedences: HashMap<char, Precedence>,
+    /// Names of the symbols that can't be represented by their own character, e.g. quoted
+    /// multi character terminals such as `"if"`, which are mapped to private use characters
+    #[serde(serialize_with = "serialize_sorted_map")]
+    pub symbol_names: HashMap<char, String>,
+}
+
+impl Default for Grammar {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Grammar {
@@ -29,6 +38,8 @@ impl Grammar {
             productions: vec![],
             terms: HashSet::new(),
             non_terms: HashSet::new(),
+            precedences: HashMap::new(),
+            symbol_names: HashMap::new(),
         }
     }
 
@@ -38,6 +49,7 @@ impl Grammar {
                 index: None,
                 driver: '@',
                 body: vec![production.driver],
+                precedence: None,
             });
         }
 
@@ -46,7 +58,7 @@ impl Grammar {
         }
 
         for symbol in &production.body {
-            if symbol.is_lowercase() && !self.terms.contains(symbol) {
+            if !symbol.is_uppercase() && !self.non_terms.contains(symbol) {
                 self.terms.insert(*symbol);
             }
         }
@@ -66,20 +78,233 @@ impl Grammar {
         }
     }
 
+    /// Returns the character representing the terminal with the given name. Names made of a
+    /// single non upper case character are represented by the character itself, every other
+    /// name is mapped to a character of the Unicode private use area.
+    pub fn intern_term(&mut self, name: &str) -> char {
+        let mut chars = name.chars();
+        let term = match (chars.next(), chars.next()) {
+            (Some(c), None) if !c.is_uppercase() => c,
+            _ => match self.get_symbol_by_name(name) {
+                Some(c) => c,
+                None => {
+                    let c = char::from_u32(0xE000 + self.symbol_names.len() as u32).unwrap();
+                    self.symbol_names.insert(c, name.to_string());
+                    c
+                }
+            },
+        };
+        self.add_term(term);
+        term
+    }
+
+    /// Returns the character representing the non terminal with the given name. Names made of
+    /// a single character are represented by the character itself, every other name is mapped
+    /// to a character of the Unicode private use area.
+    pub fn intern_non_term(&mut self, name: &str) -> char {
+        let mut chars = name.chars();
+        let non_term = match (chars.next(), chars.next()) {
+            (Some(c), None) => c,
+            _ => match self.get_symbol_by_name(name) {
+                Some(c) => c,
+                None => {
+                    let c = char::from_u32(0xE000 + self.symbol_names.len() as u32).unwrap();
+                    self.symbol_names.insert(c, name.to_string());
+                    c
+                }
+            },
+        };
+        self.add_non_term(non_term);
+        non_term
+    }
+
+    /// Creates a new non terminal named after `driver` followed by the first free index, e.g.
+    /// `A_1`, used for the helper non terminals introduced by the grammar transformations
+    pub fn new_helper_non_term(&mut self, driver: char) -> char {
+        let driver_name = self.get_symbol_name(driver);
+        let name = (1..)
+            .map(|index| format!("{}_{}", driver_name, index))
+            .find(|name| self.get_symbol_by_name(name).is_none())
+            .unwrap();
+        self.intern_non_term(&name)
+    }
+
+    /// Replaces the productions of the grammar, renumbering them in the given order. The
+    /// starting symbol, the symbol names and the precedences are kept.
+    pub fn set_productions(&mut self, productions: Vec<Production>) {
+        let start_symbol = self.get_start_symbol();
+        self.starting_prod = None;
+        self.productions.clear();
+        self.terms.clear();
+        for production in productions.iter() {
+            self.add_non_term(production.driver);
+        }
+        for (index, production) in productions.into_iter().enumerate() {
+            self.add_production(Production {
+                index: Some(index),
+                ..production
+            });
+        }
+        if let Some(start_symbol) = start_symbol {
+            self.set_start_symbol(start_symbol);
+        }
+    }
+
+    /// Returns the character of a symbol registered in `symbol_names` with the given name
+    pub fn get_symbol_by_name(&self, name: &str) -> Option<char> {
+        self.symbol_names
+            .iter()
+            .find(|(_, symbol_name)| symbol_name.as_str() == name)
+            .map(|(c, _)| *c)
+    }
+
+    /// Returns the name of a symbol as written in the grammar definition
+    pub fn get_symbol_name(&self, symbol: char) -> String {
+        match self.symbol_names.get(&symbol) {
+            Some(name) => name.clone(),
+            None => symbol.to_string(),
+        }
+    }
+
+    /// Formats a sequence of symbols. Symbols are concatenated as long as they are all single
+    /// characters, separated by spaces otherwise.
+    pub fn format_symbols(&self, symbols: &[char]) -> String {
+        let separator = if symbols.iter().any(|s| self.symbol_names.contains_key(s)) {
+            " "
+        } else {
+            ""
+        };
+        symbols
+            .iter()
+            .map(|s| self.get_symbol_name(*s))
+            .collect::<Vec<String>>()
+            .join(separator)
+    }
+
+    /// Formats a production as `A -> body`, using `ε` for empty bodies
+    pub fn format_production(&self, production: &Production) -> String {
+        if production.body.is_empty() {
+            format!("{} -> ε", self.get_symbol_name(production.driver))
+        } else {
+            format!(
+                "{} -> {}",
+                self.get_symbol_name(production.driver),
+                self.format_symbols(&production.body)
+            )
+        }
+    }
+
+    /// Returns the starting symbol of the grammar, i.e. the body of the augmented production
+    pub fn get_start_symbol(&self) -> Option<char> {
+        self.starting_prod
+            .as_ref()
+            .and_then(|prod| prod.body.first().copied())
+    }
+
+    /// Overrides the starting symbol, which otherwise is the driver of the first production
+    pub fn set_start_symbol(&mut self, start_symbol: char) {
+        self.starting_prod = Some(Production {
+            index: None,
+            driver: '@',
+            body: vec![start_symbol],
+            precedence: None,
+        });
+    }
+
+    /// Returns the grammar in the textual format read by `create_grammar_from_str`, grouping the
+    /// productions by driver. EBNF constructs are not preserved: the helper non terminals
+    /// generated by the desugaring are printed as ordinary rules.
+    pub fn get_definition_string(&self) -> String {
+        let mut rv = String::new();
+
+        let mut precedences: Vec<(&char, &Precedence)> = self.precedences.iter().collect();
+        precedences
+            .sort_by_key(|(term, precedence)| (precedence.level, self.get_symbol_name(**term)));
+        for level in precedences.chunk_by(|a, b| a.1.level == b.1.level) {
+            let directive = match level[0].1.associativity {
+                Associativity::Left => "%left",
+                Associativity::Right => "%right",
+                Associativity::NonAssoc => "%nonassoc",
+            };
+            let terms: Vec<String> = level
+                .iter()
+                .map(|(term, _)| self.get_definition_symbol(**term))
+                .collect();
+            rv.push_str(&format!("{} {}\n", directive, terms.join(" ")));
+        }
+
+        let sorted_non_terms = self.get_sorted_non_terms();
+        if let Some(start_symbol) = self.get_start_symbol()
+            && sorted_non_terms.first() != Some(&start_symbol)
+        {
+            rv.push_str(&format!("%start {}\n", self.get_symbol_name(start_symbol)));
+        }
+        if !rv.is_empty() {
+            rv.push('\n');
+        }
+
+        for driver in sorted_non_terms {
+            let bodies: Vec<String> = self
+                .productions
+                .iter()
+                .filter(|prod| prod.driver == driver)
+                .map(|prod| {
+                    let mut body: Vec<String> = prod
+                        .body
+                        .iter()
+                        .map(|symbol| self.get_definition_symbol(*symbol))
+                        .collect();
+                    if body.is_empty() {
+                        body.push(String::from("ε"));
+                    }
+                    if let Some(precedence) = prod.precedence {
+                        body.push(format!("%prec {}", self.get_definition_symbol(precedence)));
+                    }
+                    body.join(" ")
+                })
+                .collect();
+            rv.push_str(&format!(
+                "{} -> {} .\n",
+                self.get_symbol_name(driver),
+                bodies.join(" | ")
+            ));
+        }
+
+        rv
+    }
+
+    /// Returns a symbol as it has to be written in a grammar definition: terminals that are not
+    /// single alphanumeric characters are quoted
+    pub fn get_definition_symbol(&self, symbol: char) -> String {
+        let name = self.get_symbol_name(symbol);
+        if self.non_terms.contains(&symbol)
+            || (name.chars().count() == 1 && symbol.is_alphanumeric() && !symbol.is_uppercase())
+        {
+            name
+        } else if name.contains('\'') {
+            format!("\"{}\"", name)
+        } else {
+            format!("'{}'", name)
+        }
+    }
+
     /// Returns a vector of the grammar's terminals sorted as they would be in a parsing table,
     /// i.e. in alphabetical order. Note that this does not include the end of input '$' symbol
     pub fn get_sorted_terms(&self) -> Vec<char> {
         let mut sorted_terms: Vec<char> = self.terms.iter().cloned().collect();
-        sorted_terms.sort();
+        sorted_terms.sort_by_key(|term| self.get_symbol_name(*term));
         sorted_terms
     }
 
     /// Returns a vector of the grammar's non terminals sorted as they would be in a parsing table,
     /// i.e. in the order of production drivers specified in the grammar.
     pub fn get_sorted_non_terms(&self) -> Vec<char> {
-        let mut sorted_non_terms: Vec<char> =
-            self.productions.iter().map(|prod| prod.driver).collect();
-        sorted_non_terms.dedup();
+        let mut sorted_non_terms: Vec<char> = vec![];
+        for prod in self.productions.iter() {
+            if !sorted_non_terms.contains(&prod.driver) {
+                sorted_non_terms.push(prod.driver);
+            }
+        }
         sorted_non_terms
     }
 }
@@ -87,11 +312,17 @@ impl Grammar {
 impl std::fmt::Display for Grammar {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         for production in &self.productions {
-            let body_str: String = production.body.iter().collect();
+            let body_str = self.format_symbols(&production.body);
             let prod_index = production
                 .index
                 .map_or("?".to_string(), |idx| idx.to_string());
-            writeln!(f, "{}\t{} -> {}", prod_index, production.driver, body_str)?;
+            writeln!(
+                f,
+                "{}\t{} -> {}",
+                prod_index,
+                self.get_symbol_name(production.driver),
+                body_str
+            )?;
         }
         Ok(())
     }
@@ -1,10 +1,14 @@
 use std::collections::HashMap;
 
-use crate::lr0::get_parsing_automaton;
+use crate::lr0::{Lr0Automaton, Lr0Item, get_parsing_automaton, get_sorted_edges};
+use crate::output::{AutomatonView, dot::DotStyle, tikz};
 
 use super::{
     grammar::Grammar,
-    parse_structs::{Action, FirstFollowSet},
+    locale::Language,
+    notation::Notation,
+    parse_structs::{Action, FirstFollowSet, ParsingTable, ParsingTableKind},
+    precedence::ConflictResolution,
 };
 
 use base64::Engine as _;
@@ -14,9 +18,13 @@ pub struct LatexFormatOutputFormatDescriptor {
     pub grammophone_link: bool,
     pub graphviz_link: bool,
     pub grammar_definition: bool,
+    pub production_list: bool,
     pub lr0_parsing_table: bool,
     pub slr1_parsing_table: bool,
     pub first_follow_set: bool,
+    pub lr0_automaton: bool,
+    pub item_sets: bool,
+    pub goto_table: bool,
 }
 
 impl Default for LatexFormatOutputFormatDescriptor {
@@ -30,37 +38,163 @@ impl LatexFormatOutputFormatDescriptor {
         grammophone_link: true,
         graphviz_link: true,
         grammar_definition: true,
+        production_list: true,
         lr0_parsing_table: true,
         slr1_parsing_table: true,
         first_follow_set: true,
+        lr0_automaton: false,
+        item_sets: false,
+        goto_table: false,
+    };
+
+    /// The full report with the LR(0) automaton drawn with TikZ, its item sets and its GOTO
+    /// function too
+    pub const ALL: Self = Self {
+        lr0_automaton: true,
+        item_sets: true,
+        goto_table: true,
+        ..Self::FULL
     };
 
     pub const NO_LINKS: Self = Self {
         grammophone_link: false,
         graphviz_link: false,
         grammar_definition: true,
+        production_list: true,
         lr0_parsing_table: true,
         slr1_parsing_table: true,
         first_follow_set: true,
+        lr0_automaton: false,
+        item_sets: false,
+        goto_table: false,
     };
 }
 
+/// Language and notation of the LaTeX output
+#[derive(Clone, Debug, Default)]
+pub struct LatexStyle {
+    pub language: Language,
+    pub notation: Notation,
+}
+
+/// Background of the cells of the parsing tables holding more than one action
+const CONFLICT_CELL_COLOR: &str = "red!25";
+
+/// Preamble loading the packages the fragments rely on. The Unicode characters which may be
+/// left in the text (e.g. in the reasons of the classification) are mapped to math symbols.
+const LATEX_PREAMBLE: &str = "\\documentclass{article}
+\\usepackage[utf8]{inputenc}
+\\usepackage[T1]{fontenc}
+\\usepackage{amsmath}
+\\usepackage{booktabs}
+\\usepackage{float}
+\\usepackage[table]{xcolor}
+\\usepackage{hyperref}
+\\usepackage{adjustbox}
+\\usepackage{tikz}
+\\usetikzlibrary{automata, positioning, shapes.multipart}
+\\DeclareUnicodeCharacter{03B5}{$\\epsilon$}
+\\DeclareUnicodeCharacter{2022}{$\\bullet$}
+\\DeclareUnicodeCharacter{2192}{$\\rightarrow$}
+";
+
+/// Wraps the body into a complete document, which can be compiled with `pdflatex` on its own
+pub fn wrap_latex_document(body: &str) -> String {
+    format!(
+        "{}\n\\begin{{document}}\n\n{}\n\n\\end{{document}}",
+        LATEX_PREAMBLE,
+        body.trim()
+    )
+}
+
+/// Returns a heading for a section of a complete document, labelled `sec:<label>`
+pub fn generate_latex_section_heading(title: &str, label: &str) -> String {
+    format!(
+        "\\section*{{{}}}\\label{{sec:{}}}\n",
+        escape_latex(title),
+        label
+    )
+}
+
+/// Returns the name used in the labels of the parsing table of the given kind, e.g.
+/// `slr1-parsing-table`
+pub fn get_parsing_table_label(kind: ParsingTableKind) -> String {
+    let name = clap::ValueEnum::to_possible_value(&kind)
+        .map(|value| value.get_name().to_string())
+        .unwrap_or_default();
+    format!("{}-parsing-table", name)
+}
+
+/// A part of the report: the comment preceding it in the fragments and the heading and label
+/// of its section in a complete document, which the links to the online tools have not
+struct LatexSection {
+    comment: &'static str,
+    heading: Option<(String, String)>,
+    body: String,
+}
+
+/// Escapes the characters having a special meaning in LaTeX text mode
+pub fn escape_latex(text: &str) -> String {
+    let mut rv = String::new();
+    for c in text.chars() {
+        match c {
+            '$' | '&' | '%' | '#' | '_' | '{' | '}' => {
+                rv.push('\\');
+                rv.push(c);
+            }
+            '\\' => rv.push_str("\\textbackslash{}"),
+            '^' => rv.push_str("\\textasciicircum{}"),
+            '~' => rv.push_str("\\textasciitilde{}"),
+            'ε' => rv.push_str("$\\epsilon$"),
+            'λ' => rv.push_str("$\\lambda$"),
+            '•' => rv.push_str("$\\bullet$"),
+            '·' => rv.push_str("$\\cdot$"),
+            '→' => rv.push_str("$\\rightarrow$"),
+            _ => rv.push(c),
+        }
+    }
+    rv.replace("->", "$\\rightarrow$")
+}
+
 impl Grammar {
+    /// Returns the symbol formatted for LaTeX math mode
+    fn get_latex_math_symbol(&self, symbol: char) -> String {
+        let name = self.get_symbol_name(symbol);
+        if name.chars().count() == 1 && symbol.is_alphanumeric() {
+            name
+        } else if let Some((base, index)) = name.rsplit_once('_')
+            && !base.is_empty()
+            && !index.is_empty()
+            && index.chars().all(|c| c.is_ascii_digit())
+            && self.non_terms.contains(&symbol)
+        {
+            // Helper non terminals generated by the EBNF desugaring, e.g. `A_1`
+            let base = if base.chars().count() == 1 {
+                base.to_string()
+            } else {
+                format!("\\text{{{}}}", escape_latex(base))
+            };
+            format!("{}_{{{}}}", base, index)
+        } else {
+            format!("\\text{{{}}}", escape_latex(&name))
+        }
+    }
+
+    /// Returns the symbol formatted for LaTeX text mode
+    fn get_latex_text_symbol(&self, symbol: char) -> String {
+        escape_latex(&self.get_symbol_name(symbol))
+    }
+
     fn get_grammar_as_plain_text(&self) -> String {
-        let mut sorted_non_terms: Vec<char> =
-            self.productions.iter().map(|prod| prod.driver).collect();
-        sorted_non_terms.dedup();
+        let sorted_non_terms = self.get_sorted_non_terms();
 
         let mut grammar_str = String::new();
 
         let mut productions_by_driver = HashMap::new();
         for prod in self.productions.iter() {
-            if !productions_by_driver.contains_key(&prod.driver) {
-                productions_by_driver.insert(prod.driver, vec![]);
-            }
             productions_by_driver
-                .get_mut(&prod.driver)
-                .unwrap()
+                .entry(prod.driver)
+                .or_insert_with(Vec::new)
                 .push(prod);
         }
         for driver in sorted_non_terms.iter() {
@@ -69,25 +203,32 @@ impl Grammar {
                 bodies.push(
                     prod.body
                         .iter()
-                        .map(|c| c.to_string())
+                        .map(|c| self.get_symbol_name(*c))
                         .collect::<Vec<String>>()
                         .join(" "),
                 );
             }
-            grammar_str.push_str(format!("{} -> {} .\n", driver, bodies.join(" | ")).as_str());
+            grammar_str.push_str(
+                format!(
+                    "{} -> {} .\n",
+                    self.get_symbol_name(*driver),
+                    bodies.join(" | ")
+                )
+                .as_str(),
+            );
         }
 
         grammar_str
     }
 
-    fn generate_graphviz_link(&self) -> String {
-        let automaton = get_parsing_automaton(&self);
-        let dot_string = automaton.generate_dot_notation_string();
+    fn generate_graphviz_link(&self, notation: &Notation) -> String {
+        let automaton = get_parsing_automaton(self);
+        let dot_string = automaton.generate_dot_notation_string(notation, &DotStyle::default());
         urlencoding::encode(dot_string.as_str()).into_owned()
     }
 
     fn generate_grammophone_link(&self) -> String {
-        let mut grammar_str = self.get_grammar_as_plain_text();
+        let grammar_str = self.get_grammar_as_plain_text();
         format!(
             "https://mdaines.github.io/grammophone/?s={}",
             base64::engine::general_purpose::STANDARD.encode(grammar_str)
@@ -96,10 +237,12 @@ impl Grammar {
 
     fn generate_parsing_table_latex(
         &self,
-        parsing_table: &Vec<HashMap<char, Vec<Action>>>,
-        sorted_terms: &Vec<char>,
-        sorted_non_terms: &Vec<char>,
-        caption: Option<&str>,
+        parsing_table: &[HashMap<char, Vec<Action>>],
+        sorted_terms: &[char],
+        sorted_non_terms: &[char],
+        resolutions: &[ConflictResolution],
+        kind: ParsingTableKind,
+        style: &LatexStyle,
     ) -> String {
         let mut rv = String::new();
 
@@ -114,34 +257,55 @@ impl Grammar {
         );
         rv.push_str("\\toprule\n");
         let header = format!(
-            "States & {} & {}\\\\\n",
+            "{} & {} & {}\\\\\n",
+            style.language.states(),
             sorted_terms
                 .iter()
-                .map(|c| if *c == '$' {
-                    "\\$".to_string()
-                } else {
-                    c.to_string()
-                })
+                .map(|c| self.get_latex_text_symbol(*c))
                 .collect::<Vec<String>>()
                 .join(" & "),
             sorted_non_terms
                 .iter()
-                .map(|c| c.to_string())
+                .map(|c| self.get_latex_text_symbol(*c))
                 .collect::<Vec<String>>()
                 .join(" & ")
         );
         rv.push_str(header.as_str());
         rv.push_str("\\midrule\n");
 
+        let mut has_conflicts = false;
         for (node_index, row) in parsing_table.iter().enumerate() {
             let mut row_str = vec![];
             for term in sorted_terms.iter() {
-                if let Some(actions) = row.get(term) {
-                    let actions_str: Vec<String> =
-                        actions.iter().map(|action| action.to_string()).collect();
+                let resolution = resolutions
+                    .iter()
+                    .find(|r| r.state == node_index && r.symbol == *term);
+                if let Some(resolution) = resolution {
+                    // Cells solved by precedence are underlined
+                    row_str.push(format!(
+                        "\\underline{{{}}}",
+                        resolution
+                            .kept
+                            .as_ref()
+                            .map_or(String::from("err"), |action| style.notation.action(action))
+                    ));
+                } else if let Some(actions) = row.get(term) {
+                    let actions_str: Vec<String> = actions
+                        .iter()
+                        .map(|action| style.notation.action(action))
+                        .collect();
                     // actions_str = actions_str.join(", ");
                     // join("/");
-                    row_str.push(actions_str.join("/"));
+                    if actions.len() > 1 {
+                        has_conflicts = true;
+                        row_str.push(format!(
+                            "\\cellcolor{{{}}}{}",
+                            CONFLICT_CELL_COLOR,
+                            actions_str.join("/")
+                        ));
+                    } else {
+                        row_str.push(actions_str.join("/"));
+                    }
                 } else {
                     row_str.push(String::from(" "));
                 }
@@ -149,8 +313,10 @@ impl Grammar {
 
             for non_term in sorted_non_terms.iter() {
                 if let Some(actions) = row.get(non_term) {
-                    let actions_str: Vec<String> =
-                        actions.iter().map(|action| action.to_string()).collect();
+                    let actions_str: Vec<String> = actions
+                        .iter()
+                        .map(|action| style.notation.action(action))
+                        .collect();
                     // actions_str = actions_str.join(", ");
                     // join("/");
                     row_str.push(actions_str.join("/"));
@@ -159,23 +325,157 @@ impl Grammar {
                 }
             }
 
-            rv.push_str(format!("s{} & {} \\\\ \n", node_index, row_str.join(" & ")).as_str());
+            rv.push_str(
+                format!(
+                    "{} & {} \\\\ \n",
+                    escape_latex(&style.notation.state(node_index)),
+                    row_str.join(" & ")
+                )
+                .as_str(),
+            );
         }
 
         rv.push_str("\\bottomrule\n");
         rv.push_str("\\end{tabular}\n");
-        if let Some(caption) = caption {
+        let caption = style.language.parsing_table(kind);
+        let mut notes = vec![];
+        if has_conflicts {
+            notes.push(style.language.conflict_cells_note());
+        }
+        if !resolutions.is_empty() {
+            notes.push(style.language.resolved_cells_note());
+        }
+        if notes.is_empty() {
             rv.push_str(format!("\\caption{{{}}}", caption).as_str());
+        } else {
+            rv.push_str(format!("\\caption{{{} ({})}}", caption, notes.join("; ")).as_str());
         }
+        rv.push_str(format!("\\label{{tab:{}}}", get_parsing_table_label(kind)).as_str());
         rv.push_str("\\end{table}");
         rv
     }
 
+    fn generate_ll1_table_latex(
+        &self,
+        table: &HashMap<char, HashMap<char, Vec<usize>>>,
+        sorted_terms: &[char],
+        sorted_non_terms: &[char],
+        kind: ParsingTableKind,
+        style: &LatexStyle,
+    ) -> String {
+        let mut rv = String::new();
+
+        rv.push_str("\\begin{table}[H]");
+        rv.push_str("\\centering");
+        rv.push_str(
+            format!(
+                "\\begin{{tabular}}{{{}}}\n",
+                "c".repeat(sorted_terms.len() + 1)
+            )
+            .as_str(),
+        );
+        rv.push_str("\\toprule\n");
+        rv.push_str(
+            format!(
+                "{} & {}\\\\\n",
+                style.language.symbol(),
+                sorted_terms
+                    .iter()
+                    .map(|c| self.get_latex_text_symbol(*c))
+                    .collect::<Vec<String>>()
+                    .join(" & ")
+            )
+            .as_str(),
+        );
+        rv.push_str("\\midrule\n");
+
+        for non_term in sorted_non_terms.iter() {
+            let Some(row) = table.get(non_term) else {
+                continue;
+            };
+            let mut row_str = vec![];
+            for term in sorted_terms.iter() {
+                match row.get(term) {
+                    Some(prod_indices) if !prod_indices.is_empty() => row_str.push(
+                        prod_indices
+                            .iter()
+                            .map(|idx| {
+                                escape_latex(
+                                    &style.notation.production(self, &self.productions[*idx]),
+                                )
+                            })
+                            .collect::<Vec<String>>()
+                            .join(" / "),
+                    ),
+                    _ => row_str.push(String::from(" ")),
+                }
+            }
+            rv.push_str(
+                format!(
+                    "{} & {} \\\\ \n",
+                    self.get_latex_text_symbol(*non_term),
+                    row_str.join(" & ")
+                )
+                .as_str(),
+            );
+        }
+
+        rv.push_str("\\bottomrule\n");
+        rv.push_str("\\end{tabular}\n");
+        rv.push_str(format!("\\caption{{{}}}", style.language.parsing_table(kind)).as_str());
+        rv.push_str(format!("\\label{{tab:{}}}", get_parsing_table_label(kind)).as_str());
+        rv.push_str("\\end{table}");
+        rv
+    }
+
+    /// Returns the LaTeX code of a parsing table of the given kind
+    pub fn generate_parsing_table_kind_latex(
+        &self,
+        kind: ParsingTableKind,
+        parsing_table: &ParsingTable,
+        style: &LatexStyle,
+    ) -> String {
+        let mut sorted_terms: Vec<char> = self.get_sorted_terms();
+        sorted_terms.push('$');
+        let sorted_non_terms: Vec<char> = self.get_sorted_non_terms();
+
+        match parsing_table {
+            ParsingTable::Ll1(table) => {
+                self.generate_ll1_table_latex(table, &sorted_terms, &sorted_non_terms, kind, style)
+            }
+            ParsingTable::Lr { rows, resolutions } => self.generate_parsing_table_latex(
+                rows,
+                &sorted_terms,
+                &sorted_non_terms,
+                resolutions,
+                kind,
+                style,
+            ),
+        }
+    }
+
+    /// Returns the LaTeX table of the FIRST and FOLLOW sets of every non terminal
+    pub fn generate_first_follow_latex(
+        &self,
+        first_follow_set: Option<&HashMap<char, FirstFollowSet>>,
+        style: &LatexStyle,
+    ) -> String {
+        let mut sorted_terms: Vec<char> = self.get_sorted_terms();
+        sorted_terms.push('$');
+        self.generate_first_follow_table_latex(
+            first_follow_set,
+            &sorted_terms,
+            &self.get_sorted_non_terms(),
+            style,
+        )
+    }
+
     fn generate_first_follow_table_latex(
         &self,
         first_follow_set: Option<&HashMap<char, FirstFollowSet>>,
-        sorted_terms: &Vec<char>,
-        sorted_non_terms: &Vec<char>,
+        sorted_terms: &[char],
+        sorted_non_terms: &[char],
+        style: &LatexStyle,
     ) -> String {
         let mut rv = String::new();
 
@@ -192,17 +492,20 @@ impl Grammar {
         rv.push_str("\\centering");
         rv.push_str("\\begin{tabular}{cccc}\n");
         rv.push_str("\\toprule\n");
-        rv.push_str("Symbol & First\\-set & Follow\\-set & Nullable\\\\\n");
+        rv.push_str(
+            format!(
+                "{} & {} & {} & {}\\\\\n",
+                style.language.symbol(),
+                style.language.first_set(),
+                style.language.follow_set(),
+                style.language.nullable()
+            )
+            .as_str(),
+        );
         rv.push_str("\\midrule\n");
         for non_term in sorted_non_terms.iter() {
             if let Some(set) = first_follow_set.get(non_term) {
-                let espace_latex_chars = |e: char| {
-                    if e == '$' {
-                        "\\$".to_string()
-                    } else {
-                        e.to_string()
-                    }
-                };
+                let espace_latex_chars = |e: char| self.get_latex_text_symbol(e);
 
                 let mut first_set = vec![];
                 for c in sorted_terms.iter() {
@@ -231,11 +534,18 @@ impl Grammar {
                     .collect::<Vec<String>>()
                     .join(",");
 
-                let nullable_str = if set.nullable { "Yes" } else { "No" };
+                let nullable_str = if set.nullable {
+                    style.language.yes()
+                } else {
+                    style.language.no()
+                };
                 rv.push_str(
                     format!(
                         "{} & {} & {} & {}\\\\\n",
-                        non_term, first_set_str, follow_set_str, nullable_str
+                        self.get_latex_text_symbol(*non_term),
+                        first_set_str,
+                        follow_set_str,
+                        nullable_str
                     )
                     .as_str(),
                 );
@@ -243,28 +553,232 @@ impl Grammar {
         }
         rv.push_str("\\bottomrule\n");
         rv.push_str("\\end{tabular}\n");
+        rv.push_str(format!("\\caption{{{}}}", style.language.first_follow_sets()).as_str());
+        rv.push_str("\\label{tab:first-follow}");
         rv.push_str("\\end{table}");
         rv
     }
 
-    pub fn generate_latex_string(&self, descriptor: LatexFormatOutputFormatDescriptor) -> String {
+    /// Returns the body of a production formatted for LaTeX math mode
+    fn get_latex_math_body(&self, body: &[char], notation: &Notation) -> String {
+        if body.is_empty() {
+            return notation.latex_epsilon();
+        }
+        // Multi character symbols need explicit spacing in math mode
+        let separator = if body.iter().any(|c| self.symbol_names.contains_key(c)) {
+            " \\ "
+        } else {
+            ""
+        };
+        body.iter()
+            .map(|c| self.get_latex_math_symbol(*c))
+            .collect::<Vec<String>>()
+            .join(separator)
+    }
+
+    /// Returns the table of the productions numbered as in the reduce actions
+    pub fn generate_productions_latex(&self, style: &LatexStyle) -> String {
+        let mut rv = String::new();
+        rv.push_str("\\begin{table}[H]");
+        rv.push_str("\\centering");
+        rv.push_str("\\begin{tabular}{rl}\n");
+        rv.push_str("\\toprule\n");
+        rv.push_str(format!("\\# & {}\\\\\n", style.language.production()).as_str());
+        rv.push_str("\\midrule\n");
+        for (index, prod) in self.productions.iter().enumerate() {
+            rv.push_str(
+                format!(
+                    "{} & ${} {} {}$\\\\\n",
+                    style.notation.production_number(index),
+                    self.get_latex_math_symbol(prod.driver),
+                    style.notation.latex_arrow(),
+                    self.get_latex_math_body(&prod.body, &style.notation)
+                )
+                .as_str(),
+            );
+        }
+        rv.push_str("\\bottomrule\n");
+        rv.push_str("\\end{tabular}\n");
+        rv.push_str(format!("\\caption{{{}}}", style.language.productions()).as_str());
+        rv.push_str("\\label{tab:productions}");
+        rv.push_str("\\end{table}");
+        rv
+    }
+
+    /// Returns the item formatted for LaTeX math mode, spaced like the bodies of the
+    /// productions
+    fn get_latex_math_item(&self, item: &Lr0Item, notation: &Notation) -> String {
+        let body = &item.production.body;
+        let separator = if body.iter().any(|c| self.symbol_names.contains_key(c)) {
+            " \\ "
+        } else {
+            " "
+        };
+        let (before_dot, after_dot) = body.split_at(item.dot_position());
+        let symbols: Vec<String> = before_dot
+            .iter()
+            .map(|c| self.get_latex_math_symbol(*c))
+            .chain(std::iter::once(notation.latex_item_dot()))
+            .chain(after_dot.iter().map(|c| self.get_latex_math_symbol(*c)))
+            .collect();
+        format!(
+            "{} {} {}",
+            self.get_latex_math_symbol(item.production.driver),
+            notation.latex_arrow(),
+            symbols.join(separator)
+        )
+    }
+
+    /// Returns the item sets of the LR(0) automaton as an `align*` environment, written as
+    /// `I = \{ kernel ; closure \}` with the closure on a line of its own
+    pub fn generate_item_sets_latex(&self, automaton: &Lr0Automaton, style: &LatexStyle) -> String {
+        let format_items = |items: &[Lr0Item]| -> String {
+            items
+                .iter()
+                .map(|item| self.get_latex_math_item(item, &style.notation))
+                .collect::<Vec<String>>()
+                .join(",\\ ")
+        };
+        let mut rv = String::new();
+        rv.push_str("\\begin{align*}\n");
+        for (index, node) in automaton.nodes.iter().enumerate() {
+            let name = format!("\\text{{{}}}", escape_latex(&style.notation.state(index)));
+            if node.closure.is_empty() {
+                rv.push_str(&format!(
+                    "{} &= \\{{\\, {} \\,\\}} \\\\\n",
+                    name,
+                    format_items(&node.kernel)
+                ));
+            } else {
+                rv.push_str(&format!(
+                    "{} &= \\{{\\, {} \\;; \\\\\n&\\qquad {} \\,\\}} \\\\\n",
+                    name,
+                    format_items(&node.kernel),
+                    format_items(&node.closure)
+                ));
+            }
+        }
+        rv.push_str("\\end{align*}\n");
+        rv
+    }
+
+    /// Returns the GOTO function of the LR(0) automaton as a table laid out like the parsing
+    /// tables, with the target state of every transition in the cells
+    pub fn generate_goto_table_latex(
+        &self,
+        automaton: &Lr0Automaton,
+        style: &LatexStyle,
+    ) -> String {
+        let symbols: Vec<char> = self
+            .get_sorted_terms()
+            .into_iter()
+            .chain(self.get_sorted_non_terms())
+            .collect();
+        let edges = get_sorted_edges(&automaton.edges);
+
+        let mut rv = String::new();
+        rv.push_str("\\begin{table}[H]");
+        rv.push_str("\\centering");
+        rv.push_str(format!("\\begin{{tabular}}{{{}}}\n", "c".repeat(symbols.len() + 1)).as_str());
+        rv.push_str("\\toprule\n");
+        rv.push_str(
+            format!(
+                "{} & {}\\\\\n",
+                style.language.states(),
+                symbols
+                    .iter()
+                    .map(|c| self.get_latex_text_symbol(*c))
+                    .collect::<Vec<String>>()
+                    .join(" & ")
+            )
+            .as_str(),
+        );
+        rv.push_str("\\midrule\n");
+        for node_index in 0..automaton.nodes.len() {
+            let row: Vec<String> = symbols
+                .iter()
+                .map(|symbol| {
+                    edges
+                        .iter()
+                        .find(|edge| edge.from == node_index && edge.symbol == *symbol)
+                        .map_or(String::from(" "), |edge| {
+                            escape_latex(&style.notation.state(edge.to))
+                        })
+                })
+                .collect();
+            rv.push_str(
+                format!(
+                    "{} & {} \\\\ \n",
+                    escape_latex(&style.notation.state(node_index)),
+                    row.join(" & ")
+                )
+                .as_str(),
+            );
+        }
+        rv.push_str("\\bottomrule\n");
+        rv.push_str("\\end{tabular}\n");
+        rv.push_str(format!("\\caption{{{}}}", style.language.goto_function()).as_str());
+        rv.push_str("\\label{tab:goto}");
+        rv.push_str("\\end{table}");
+        rv
+    }
+
+    /// Returns the productions of the grammar as an `align*` environment, one line per driver
+    pub fn generate_grammar_latex(&self, notation: &Notation) -> String {
+        let mut rv = String::new();
+        rv.push_str("\\begin{align*}\n");
+        let mut productions_by_driver = HashMap::new();
+        for prod in self.productions.iter() {
+            productions_by_driver
+                .entry(prod.driver)
+                .or_insert_with(Vec::new)
+                .push(prod);
+        }
+        for driver in self.get_sorted_non_terms().iter() {
+            let bodies: Vec<String> = productions_by_driver
+                .get(driver)
+                .unwrap()
+                .iter()
+                .map(|prod| self.get_latex_math_body(&prod.body, notation))
+                .collect();
+            rv.push_str(
+                format!(
+                    "{} &{} {} \\\\\n",
+                    self.get_latex_math_symbol(*driver),
+                    notation.latex_arrow(),
+                    bodies.join(" \\mid ")
+                )
+                .as_str(),
+            );
+        }
+        rv.push_str("\\end{align*}\n");
+        rv
+    }
+
+    fn get_latex_sections(
+        &self,
+        descriptor: LatexFormatOutputFormatDescriptor,
+        style: &LatexStyle,
+    ) -> Vec<LatexSection> {
         /* ######################### Common ######################### */
         let first_follow_set = self.get_first_follow_table();
         let lr0_parsing_table = self.get_lr0_parsing_table();
-        let slr1_parsing_table =
+        let mut slr1_parsing_table =
             self.get_slr1_parsing_table(Some(&lr0_parsing_table), Some(&first_follow_set));
+        let slr1_resolutions = self.resolve_conflicts(&mut slr1_parsing_table);
 
         let mut sorted_terms: Vec<char> = self.get_sorted_terms();
         sorted_terms.push('$');
 
-        let mut sorted_non_terms: Vec<char> = self.get_sorted_non_terms();
+        let sorted_non_terms: Vec<char> = self.get_sorted_non_terms();
 
         /* ######################### Grammophone link ######################### */
         let mut grammophone_link_string = String::new();
         if descriptor.grammophone_link {
             grammophone_link_string = format!(
-                "\\href{{{}}}{{View on Grammophone}}",
-                self.generate_grammophone_link()
+                "\\href{{{}}}{{{}}}",
+                self.generate_grammophone_link(),
+                style.language.grammophone_link()
             )
         }
 
@@ -272,40 +786,20 @@ impl Grammar {
         let mut graphviz_link_string = String::new();
         if descriptor.graphviz_link {
             graphviz_link_string = format!(
-                "\\href{{https://dreampuf.github.io/GraphvizOnline/?engine=dot#{}}}{{View on Graphviz}}",
-                self.generate_graphviz_link()
+                "\\href{{https://dreampuf.github.io/GraphvizOnline/?engine=dot#{}}}{{{}}}",
+                self.generate_graphviz_link(&style.notation),
+                style.language.graphviz_link()
             );
         }
 
         /* ######################### Grammar ######################### */
         let mut productions_string = String::new();
         if descriptor.grammar_definition {
-            productions_string.push_str("\\begin{align*}\n");
-            let mut productions_by_driver = HashMap::new();
-            for prod in self.productions.iter() {
-                if !productions_by_driver.contains_key(&prod.driver) {
-                    productions_by_driver.insert(prod.driver, vec![]);
-                }
-                productions_by_driver
-                    .get_mut(&prod.driver)
-                    .unwrap()
-                    .push(prod);
-            }
-            for driver in sorted_non_terms.iter() {
-                let mut bodies: Vec<String> = vec![];
-                for prod in productions_by_driver.get(driver).unwrap().iter() {
-                    let formatted_body = if prod.body.is_empty() {
-                        "\\epsilon".to_string()
-                    } else {
-                        prod.body.iter().collect()
-                    };
-                    bodies.push(formatted_body);
-                }
-                productions_string.push_str(
-                    format!("{} &\\rightarrow {} \\\\\n", driver, bodies.join(" \\mid ")).as_str(),
-                );
-            }
-            productions_string.push_str("\\end{align*}\n");
+            productions_string = self.generate_grammar_latex(&style.notation);
+        }
+        let mut production_list_string = String::new();
+        if descriptor.production_list {
+            production_list_string = self.generate_productions_latex(style);
         }
         let mut lr0_parsing_table_string = String::new();
         if descriptor.lr0_parsing_table {
@@ -315,7 +809,9 @@ impl Grammar {
                 &lr0_parsing_table,
                 &sorted_terms,
                 &sorted_non_terms,
-                Some("Tabella di parsing LR(0)"),
+                &[],
+                ParsingTableKind::Lr0,
+                style,
             );
         }
 
@@ -328,7 +824,9 @@ impl Grammar {
                 &slr1_parsing_table,
                 &sorted_terms,
                 &sorted_non_terms,
-                Some("Tabella di parsing SLR(1)"),
+                &slr1_resolutions,
+                ParsingTableKind::Slr1,
+                style,
             );
         }
 
@@ -340,24 +838,152 @@ impl Grammar {
                 Some(&first_follow_set),
                 &sorted_terms,
                 &sorted_non_terms,
+                style,
             );
         }
 
-        format!(
-            "
-% Grammophone link\n{} \n\n
-% Graphviz link\n{} \n\n
-% Grammar\n{} \n\n
-% Lr0 parsing table\n{} \n\n
-% Slr1 parsing table\n{} \n\n
-% First-follow set\n{}
-",
-            grammophone_link_string,
-            graphviz_link_string,
-            productions_string,
-            lr0_parsing_table_string,
-            slr1_parsing_table_string,
-            first_follow_table_string
-        )
+        /* ######################### lr0 automaton ######################### */
+        let automaton = get_parsing_automaton(self);
+        let mut lr0_automaton_string = String::new();
+        if descriptor.lr0_automaton {
+            lr0_automaton_string = tikz::render_figure(
+                &AutomatonView::from_lr0(&automaton, &style.notation),
+                &style.language.automaton("LR(0)"),
+                "lr0-automaton",
+            );
+        }
+
+        /* ######################### Item sets and goto table ######################### */
+        let mut item_sets_string = String::new();
+        if descriptor.item_sets {
+            item_sets_string = self.generate_item_sets_latex(&automaton, style);
+        }
+        let mut goto_table_string = String::new();
+        if descriptor.goto_table {
+            goto_table_string = self.generate_goto_table_latex(&automaton, style);
+        }
+
+        vec![
+            LatexSection {
+                comment: "Grammophone link",
+                heading: None,
+                body: grammophone_link_string,
+            },
+            LatexSection {
+                comment: "Graphviz link",
+                heading: None,
+                body: graphviz_link_string,
+            },
+            LatexSection {
+                comment: "Grammar",
+                heading: Some((
+                    style.language.grammar().to_string(),
+                    String::from("grammar"),
+                )),
+                body: productions_string,
+            },
+            LatexSection {
+                comment: "Production list",
+                heading: Some((
+                    style.language.productions().to_string(),
+                    String::from("productions"),
+                )),
+                body: production_list_string,
+            },
+            LatexSection {
+                comment: "Lr0 parsing table",
+                heading: Some((
+                    style.language.parsing_table(ParsingTableKind::Lr0),
+                    get_parsing_table_label(ParsingTableKind::Lr0),
+                )),
+                body: lr0_parsing_table_string,
+            },
+            LatexSection {
+                comment: "Slr1 parsing table",
+                heading: Some((
+                    style.language.parsing_table(ParsingTableKind::Slr1),
+                    get_parsing_table_label(ParsingTableKind::Slr1),
+                )),
+                body: slr1_parsing_table_string,
+            },
+            LatexSection {
+                comment: "First-follow set",
+                heading: Some((
+                    style.language.first_follow_sets().to_string(),
+                    String::from("first-follow"),
+                )),
+                body: first_follow_table_string,
+            },
+            LatexSection {
+                comment: "Lr0 automaton",
+                heading: Some((
+                    style.language.automaton("LR(0)"),
+                    String::from("lr0-automaton"),
+                )),
+                body: lr0_automaton_string,
+            },
+            LatexSection {
+                comment: "Item sets",
+                heading: Some((
+                    style.language.item_sets().to_string(),
+                    String::from("item-sets"),
+                )),
+                body: item_sets_string,
+            },
+            LatexSection {
+                comment: "Goto table",
+                heading: Some((
+                    style.language.goto_function().to_string(),
+                    String::from("goto"),
+                )),
+                body: goto_table_string,
+            },
+        ]
+    }
+
+    /// Returns the sections of the report selected by the descriptor as fragments, to be
+    /// included in a document loading `booktabs`, `float` and `hyperref`
+    pub fn generate_latex_string(
+        &self,
+        descriptor: LatexFormatOutputFormatDescriptor,
+        style: &LatexStyle,
+    ) -> String {
+        let sections: Vec<String> = self
+            .get_latex_sections(descriptor, style)
+            .iter()
+            .map(|section| format!("% {}\n{}", section.comment, section.body))
+            .collect();
+        format!("\n{}\n", sections.join(" \n\n\n"))
+    }
+
+    /// Returns the report as a complete document, with a section for each part and the links
+    /// to the online tools at the top
+    pub fn generate_latex_document(
+        &self,
+        descriptor: LatexFormatOutputFormatDescriptor,
+        style: &LatexStyle,
+    ) -> String {
+        let sections = self.get_latex_sections(descriptor, style);
+        let links: Vec<&str> = sections
+            .iter()
+            .filter(|section| section.heading.is_none() && !section.body.is_empty())
+            .map(|section| section.body.as_str())
+            .collect();
+        let mut parts = vec![];
+        if !links.is_empty() {
+            parts.push(links.join(" \\quad\n"));
+        }
+        for section in sections.iter() {
+            if let Some((heading, label)) = &section.heading
+                && !section.body.is_empty()
+            {
+                parts.push(format!(
+                    "{}{}",
+                    generate_latex_section_heading(heading, label),
+                    section.body.trim_end()
+                ));
+            }
+        }
+        wrap_latex_document(&parts.join("\n\n"))
     }
 }
@@ -0,0 +1,202 @@
+use crate::args::error::{SyntaxError, SyntaxErrorKind};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind {
+    /// Unquoted symbol, e.g. `A`, `a`, `+` or a `%token` name
+    Symbol(String),
+    /// Terminal between single or double quotes, e.g. `'+'` or `"if"`
+    Quoted(String),
+    /// `%` followed by a word, e.g. `%left` or `%prec`
+    Directive(String),
+    Arrow,
+    Pipe,
+    Dot,
+    /// One of `(`, `[` or `{`
+    Open(char),
+    /// One of `)`, `]` or `}`
+    Close(char),
+    /// `*` or `+` following a symbol or a closing bracket on the same line, e.g. `a*`, `a *` or
+    /// `(a b) +`. Outside of EBNF mode it is a plain terminal.
+    Postfix(char),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    /// 1 based line of the first character of the token
+    pub line: usize,
+    /// 1 based column (in characters) of the first character of the token
+    pub column: usize,
+    /// The token as written in the source, e.g. `'+'` for `Quoted("+")`
+    pub text: String,
+}
+
+/// Splits a grammar definition into tokens. Whitespace is insignificant and comments starting
+/// with `#` or `//` run until the end of the line. Invalid quoted terminals are reported and
+/// skipped, so that the tokens of the whole source are always returned.
+pub fn tokenize(source: &str) -> (Vec<Token>, Vec<SyntaxError>) {
+    let mut tokens = vec![];
+    let mut errors = vec![];
+
+    for (line_index, line) in source.lines().enumerate() {
+        let chars: Vec<char> = line.chars().collect();
+        let mut i = 0;
+        // Whether the last token of the line can be followed by a postfix operator
+        let mut postfix_allowed = false;
+
+        while i < chars.len() {
+            let c = chars[i];
+            let next = chars.get(i + 1).copied();
+            let start = i;
+
+            let kind = match c {
+                _ if c.is_whitespace() => {
+                    i += 1;
+                    continue;
+                }
+                '#' => break,
+                '/' if next == Some('/') => break,
+                '-' if next == Some('>') => {
+                    i += 2;
+                    TokenKind::Arrow
+                }
+                '|' => {
+                    i += 1;
+                    TokenKind::Pipe
+                }
+                '.' => {
+                    i += 1;
+                    TokenKind::Dot
+                }
+                '(' | '[' | '{' => {
+                    i += 1;
+                    TokenKind::Open(c)
+                }
+                ')' | ']' | '}' => {
+                    i += 1;
+                    TokenKind::Close(c)
+                }
+                '*' | '+' if postfix_allowed => {
+                    i += 1;
+                    TokenKind::Postfix(c)
+                }
+                '\'' | '"' => {
+                    let Some(end) = chars[i + 1..]
+                        .iter()
+                        .position(|e| *e == c)
+                        .map(|offset| i + 1 + offset)
+                    else {
+                        errors.push(SyntaxError {
+                            kind: SyntaxErrorKind::UnterminatedQuote,
+                            line: line_index + 1,
+                            column: start + 1,
+                            text: chars[i..].iter().collect(),
+                            source_line: line.to_string(),
+                        });
+                        break;
+                    };
+                    let name: String = chars[i + 1..end].iter().collect();
+                    i = end + 1;
+                    if name.is_empty() {
+                        errors.push(SyntaxError {
+                            kind: SyntaxErrorKind::EmptyQuote,
+                            line: line_index + 1,
+                            column: start + 1,
+                            text: chars[start..i].iter().collect(),
+                            source_line: line.to_string(),
+                        });
+                        continue;
+                    }
+                    TokenKind::Quoted(name)
+                }
+                '%' if next.is_some_and(|n| n.is_alphabetic()) => {
+                    i += 1;
+                    while i < chars.len() && chars[i].is_alphanumeric() {
+                        i += 1;
+                    }
+                    TokenKind::Directive(chars[start + 1..i].iter().collect())
+                }
+                _ => {
+                    i += 1;
+                    while i < chars.len() && !is_symbol_end(&chars, i) {
+                        i += 1;
+                    }
+                    TokenKind::Symbol(chars[start..i].iter().collect())
+                }
+            };
+
+            postfix_allowed = matches!(
+                kind,
+                TokenKind::Symbol(_)
+                    | TokenKind::Quoted(_)
+                    | TokenKind::Close(_)
+                    | TokenKind::Postfix(_)
+            );
+
+            tokens.push(Token {
+                kind,
+                line: line_index + 1,
+                column: start + 1,
+                text: chars[start..i].iter().collect(),
+            });
+        }
+    }
+
+    (tokens, errors)
+}
+
+fn is_symbol_end(chars: &[char], i: usize) -> bool {
+    let next = chars.get(i + 1).copied();
+    match chars[i] {
+        c if c.is_whitespace() => true,
+        '|' | '.' | '#' | '\'' | '"' | '(' | ')' | '[' | ']' | '{' | '}' | '*' | '+' => true,
+        '-' => next == Some('>'),
+        '/' => next == Some('/'),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(source: &str) -> Vec<TokenKind> {
+        let (tokens, errors) = tokenize(source);
+        assert!(errors.is_empty());
+        tokens.into_iter().map(|token| token.kind).collect()
+    }
+
+    #[test]
+    fn postfix_operators_ignore_whitespace() {
+        for source in ["S -> (a|)*b", "S -> ( a | ) * b"] {
+            assert_eq!(
+                kinds(source),
+                vec![
+                    TokenKind::Symbol("S".to_string()),
+                    TokenKind::Arrow,
+                    TokenKind::Open('('),
+                    TokenKind::Symbol("a".to_string()),
+                    TokenKind::Pipe,
+                    TokenKind::Close(')'),
+                    TokenKind::Postfix('*'),
+                    TokenKind::Symbol("b".to_string()),
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn operators_not_following_an_operand_are_symbols() {
+        assert_eq!(
+            kinds("S -> + | * a"),
+            vec![
+                TokenKind::Symbol("S".to_string()),
+                TokenKind::Arrow,
+                TokenKind::Symbol("+".to_string()),
+                TokenKind::Pipe,
+                TokenKind::Symbol("*".to_string()),
+                TokenKind::Symbol("a".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,529 @@
+use super::{
+    classify::{ConflictCell, GrammarClass, VerdictReason},
+    grammar::Grammar,
+    notation::Notation,
+    parse_structs::ParsingTableKind,
+    simulate::{ParseOutcome, Rejection, TraceAction},
+};
+
+/// Maximum number of conflicting cells listed in a failure reason
+const MAX_LISTED_CONFLICTS: usize = 3;
+
+/// Language of the captions, headers and links of the LaTeX output, which are meant for LaTeX
+/// text mode, and of the messages of the classification and of the parse trace, which are plain
+/// text (and always in English in the other formats).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum Language {
+    #[default]
+    En,
+    It,
+}
+
+impl Language {
+    pub fn grammar(&self) -> &'static str {
+        match self {
+            Language::En => "Grammar",
+            Language::It => "Grammatica",
+        }
+    }
+
+    pub fn grammophone_link(&self) -> &'static str {
+        match self {
+            Language::En => "View on Grammophone",
+            Language::It => "Visualizza su Grammophone",
+        }
+    }
+
+    pub fn graphviz_link(&self) -> &'static str {
+        match self {
+            Language::En => "View on Graphviz",
+            Language::It => "Visualizza su Graphviz",
+        }
+    }
+
+    pub fn yes(&self) -> &'static str {
+        match self {
+            Language::En => "Yes",
+            Language::It => "Sì",
+        }
+    }
+
+    pub fn no(&self) -> &'static str {
+        match self {
+            Language::En => "No",
+            Language::It => "No",
+        }
+    }
+
+    /// Answer of a test which may be inconclusive
+    pub fn maybe(&self, answer: Option<bool>) -> &'static str {
+        match answer {
+            Some(true) => self.yes(),
+            Some(false) => self.no(),
+            None => "?",
+        }
+    }
+
+    /* ######################### Productions ######################### */
+
+    pub fn productions(&self) -> &'static str {
+        match self {
+            Language::En => "Numbered productions",
+            Language::It => "Produzioni numerate",
+        }
+    }
+
+    pub fn production(&self) -> &'static str {
+        match self {
+            Language::En => "Production",
+            Language::It => "Produzione",
+        }
+    }
+
+    /* ######################### Parsing tables ######################### */
+
+    pub fn states(&self) -> &'static str {
+        match self {
+            Language::En => "States",
+            Language::It => "Stati",
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Language::En => "Symbol",
+            Language::It => "Simbolo",
+        }
+    }
+
+    pub fn parsing_table(&self, kind: ParsingTableKind) -> String {
+        match self {
+            Language::En => format!("{} parsing table", kind),
+            Language::It => format!("Tabella di parsing {}", kind),
+        }
+    }
+
+    pub fn resolved_cells_note(&self) -> &'static str {
+        match self {
+            Language::En => "underlined cells solved by precedence and associativity",
+            Language::It => "celle sottolineate risolte con precedenza e associatività",
+        }
+    }
+
+    pub fn conflict_cells_note(&self) -> &'static str {
+        match self {
+            Language::En => "coloured cells with conflicts",
+            Language::It => "celle colorate in conflitto",
+        }
+    }
+
+    /// Caption of an automaton, e.g. `LR(0)`
+    pub fn automaton(&self, name: &str) -> String {
+        match self {
+            Language::En => format!("{} automaton", name),
+            Language::It => format!("Automa {}", name),
+        }
+    }
+
+    pub fn item_sets(&self) -> &'static str {
+        match self {
+            Language::En => "Canonical collection of LR(0) item sets",
+            Language::It => "Collezione canonica degli insiemi di item LR(0)",
+        }
+    }
+
+    pub fn goto_function(&self) -> &'static str {
+        match self {
+            Language::En => "GOTO function",
+            Language::It => "Funzione GOTO",
+        }
+    }
+
+    /* ######################### FIRST and FOLLOW ######################### */
+
+    pub fn first_follow_sets(&self) -> &'static str {
+        match self {
+            Language::En => "FIRST and FOLLOW sets",
+            Language::It => "Insiemi FIRST e FOLLOW",
+        }
+    }
+
+    pub fn first_set(&self) -> &'static str {
+        match self {
+            Language::En => "First\\-set",
+            Language::It => "Insieme First",
+        }
+    }
+
+    pub fn follow_set(&self) -> &'static str {
+        match self {
+            Language::En => "Follow\\-set",
+            Language::It => "Insieme Follow",
+        }
+    }
+
+    pub fn nullable(&self) -> &'static str {
+        match self {
+            Language::En => "Nullable",
+            Language::It => "Annullabile",
+        }
+    }
+
+    /* ######################### Parse trace ######################### */
+
+    pub fn parsing_of(&self, kind: ParsingTableKind, sentence: &str) -> String {
+        match self {
+            Language::En => format!("{} parsing of {}", kind, sentence),
+            Language::It => format!("Simulazione del parsing {} di {}", kind, sentence),
+        }
+    }
+
+    /// Headers of the step, stack, input and action columns
+    pub fn trace_headers(&self) -> [&'static str; 4] {
+        match self {
+            Language::En => ["Step", "Stack", "Input", "Action"],
+            Language::It => ["Passo", "Pila", "Ingresso", "Azione"],
+        }
+    }
+
+    pub fn trace_action(
+        &self,
+        action: &TraceAction,
+        grammar: &Grammar,
+        notation: &Notation,
+    ) -> String {
+        let production = |index: &usize| notation.production(grammar, &grammar.productions[*index]);
+        match (self, action) {
+            (_, TraceAction::Expand(prod_index)) => production(prod_index),
+            (Language::En, TraceAction::Match(symbol)) => {
+                format!("match {}", grammar.get_symbol_name(*symbol))
+            }
+            (Language::It, TraceAction::Match(symbol)) => {
+                format!("confronta {}", grammar.get_symbol_name(*symbol))
+            }
+            (Language::En, TraceAction::Shift(state)) => {
+                format!("shift {}", notation.state(*state))
+            }
+            (Language::It, TraceAction::Shift(state)) => {
+                format!("sposta {}", notation.state(*state))
+            }
+            (Language::En, TraceAction::Reduce(prod_index)) => {
+                format!("reduce {}", production(prod_index))
+            }
+            (Language::It, TraceAction::Reduce(prod_index)) => {
+                format!("riduci {}", production(prod_index))
+            }
+            (Language::En, TraceAction::Accept) => String::from("accept"),
+            (Language::It, TraceAction::Accept) => String::from("accetta"),
+            (Language::En, TraceAction::Error) => String::from("error"),
+            (Language::It, TraceAction::Error) => String::from("errore"),
+            (Language::En, TraceAction::Conflict) => String::from("conflict"),
+            (Language::It, TraceAction::Conflict) => String::from("conflitto"),
+        }
+    }
+
+    pub fn parse_outcome(
+        &self,
+        outcome: &ParseOutcome,
+        grammar: &Grammar,
+        notation: &Notation,
+    ) -> String {
+        match (self, outcome) {
+            (Language::En, ParseOutcome::Accepted) => String::from("accepted"),
+            (Language::It, ParseOutcome::Accepted) => String::from("accettata"),
+            (Language::En, ParseOutcome::Rejected(rejection)) => {
+                format!("rejected: {}", self.rejection(rejection, grammar, notation))
+            }
+            (Language::It, ParseOutcome::Rejected(rejection)) => {
+                format!(
+                    "rifiutata: {}",
+                    self.rejection(rejection, grammar, notation)
+                )
+            }
+            (Language::En, ParseOutcome::Conflict(cell)) => {
+                format!("conflict: {}", self.conflict_cell(cell, grammar, notation))
+            }
+            (Language::It, ParseOutcome::Conflict(cell)) => {
+                format!("conflitto: {}", self.conflict_cell(cell, grammar, notation))
+            }
+        }
+    }
+
+    pub fn rejection(
+        &self,
+        rejection: &Rejection,
+        grammar: &Grammar,
+        notation: &Notation,
+    ) -> String {
+        let name = |symbol: &char| grammar.get_symbol_name(*symbol);
+        match (self, rejection) {
+            (Language::En, Rejection::UnknownTerminal { word }) => {
+                format!("unknown terminal `{}`", word)
+            }
+            (Language::It, Rejection::UnknownTerminal { word }) => {
+                format!("terminale sconosciuto `{}`", word)
+            }
+            (Language::En, Rejection::NoProductions) => {
+                String::from("the grammar has no productions")
+            }
+            (Language::It, Rejection::NoProductions) => {
+                String::from("la grammatica non ha produzioni")
+            }
+            (Language::En, Rejection::Mismatch { expected, found }) => {
+                format!("expected `{}`, found `{}`", name(expected), name(found))
+            }
+            (Language::It, Rejection::Mismatch { expected, found }) => {
+                format!("atteso `{}`, trovato `{}`", name(expected), name(found))
+            }
+            (
+                Language::En,
+                Rejection::NoProduction {
+                    non_term,
+                    lookahead,
+                },
+            ) => format!(
+                "no production for `{}` on `{}`",
+                name(non_term),
+                name(lookahead)
+            ),
+            (
+                Language::It,
+                Rejection::NoProduction {
+                    non_term,
+                    lookahead,
+                },
+            ) => format!(
+                "nessuna produzione per `{}` con `{}`",
+                name(non_term),
+                name(lookahead)
+            ),
+            (Language::En, Rejection::NoAction { state, lookahead }) => format!(
+                "no action in state {} on `{}`",
+                notation.state(*state),
+                name(lookahead)
+            ),
+            (Language::It, Rejection::NoAction { state, lookahead }) => format!(
+                "nessuna azione nello stato {} con `{}`",
+                notation.state(*state),
+                name(lookahead)
+            ),
+            (Language::En, Rejection::NoGoto { state, non_term }) => format!(
+                "no goto from state {} on `{}`",
+                notation.state(*state),
+                name(non_term)
+            ),
+            (Language::It, Rejection::NoGoto { state, non_term }) => format!(
+                "nessun goto dallo stato {} con `{}`",
+                notation.state(*state),
+                name(non_term)
+            ),
+            (Language::En, Rejection::TooManySteps { max_steps }) => {
+                format!("more than {} steps", max_steps)
+            }
+            (Language::It, Rejection::TooManySteps { max_steps }) => {
+                format!("più di {} passi", max_steps)
+            }
+        }
+    }
+
+    /* ######################### Classification ######################### */
+
+    pub fn classification(&self) -> &'static str {
+        match self {
+            Language::En => "Classification of the grammar",
+            Language::It => "Classificazione della grammatica",
+        }
+    }
+
+    /// Headers of the class, member and reason columns
+    pub fn classification_headers(&self) -> [&'static str; 3] {
+        match self {
+            Language::En => ["Class", "Member", "Reason"],
+            Language::It => ["Classe", "Appartiene", "Motivazione"],
+        }
+    }
+
+    pub fn class_name(&self, class: GrammarClass) -> String {
+        match (self, class) {
+            (Language::It, GrammarClass::Regular) => String::from("Regolare"),
+            (Language::It, GrammarClass::Ambiguous) => String::from("Ambigua"),
+            _ => class.to_string(),
+        }
+    }
+
+    /// A conflicting cell of a parsing table with its entries, e.g. `state s4 on a: s3/r1`
+    pub fn conflict_cell(
+        &self,
+        cell: &ConflictCell,
+        grammar: &Grammar,
+        notation: &Notation,
+    ) -> String {
+        match cell {
+            ConflictCell::Ll1 {
+                non_term,
+                term,
+                productions,
+            } => format!(
+                "M[{}, {}]: {}",
+                grammar.get_symbol_name(*non_term),
+                grammar.get_symbol_name(*term),
+                productions
+                    .iter()
+                    .map(|index| notation.production(grammar, &grammar.productions[*index]))
+                    .collect::<Vec<String>>()
+                    .join(" / ")
+            ),
+            ConflictCell::Lr {
+                state,
+                symbol,
+                actions,
+            } => format!(
+                "{} {} {} {}: {}",
+                match self {
+                    Language::En => "state",
+                    Language::It => "stato",
+                },
+                notation.state(*state),
+                match self {
+                    Language::En => "on",
+                    Language::It => "con",
+                },
+                grammar.get_symbol_name(*symbol),
+                actions
+                    .iter()
+                    .map(|action| notation.action(action))
+                    .collect::<Vec<String>>()
+                    .join("/")
+            ),
+        }
+    }
+
+    pub fn verdict_reason(
+        &self,
+        reason: &VerdictReason,
+        grammar: &Grammar,
+        notation: &Notation,
+    ) -> String {
+        let production = |index: &usize| notation.production(grammar, &grammar.productions[*index]);
+        let derivation = |derivation: &[usize]| {
+            derivation
+                .iter()
+                .map(|index| format!("r{}", notation.production_number(*index)))
+                .collect::<Vec<String>>()
+                .join(" ")
+        };
+
+        match (self, reason) {
+            (Language::En, VerdictReason::NotLinear { production: index }) => {
+                format!("{} is neither right nor left linear", production(index))
+            }
+            (Language::It, VerdictReason::NotLinear { production: index }) => {
+                format!(
+                    "{} non è né lineare destra né lineare sinistra",
+                    production(index)
+                )
+            }
+            (
+                Language::En,
+                VerdictReason::MixedLinearity {
+                    right_linear,
+                    left_linear,
+                },
+            ) => format!(
+                "mixes right linear ({}) and left linear ({}) productions",
+                production(right_linear),
+                production(left_linear)
+            ),
+            (
+                Language::It,
+                VerdictReason::MixedLinearity {
+                    right_linear,
+                    left_linear,
+                },
+            ) => format!(
+                "mescola produzioni lineari destre ({}) e lineari sinistre ({})",
+                production(right_linear),
+                production(left_linear)
+            ),
+            (Language::En, VerdictReason::LeftLinear) => String::from("left linear"),
+            (Language::It, VerdictReason::LeftLinear) => String::from("lineare sinistra"),
+            (Language::En, VerdictReason::RightLinear) => String::from("right linear"),
+            (Language::It, VerdictReason::RightLinear) => String::from("lineare destra"),
+            (Language::En, VerdictReason::NoConflicts) => String::from("no conflicts"),
+            (Language::It, VerdictReason::NoConflicts) => String::from("nessun conflitto"),
+            (_, VerdictReason::Conflicts { cells }) => {
+                let listed = cells
+                    .iter()
+                    .take(MAX_LISTED_CONFLICTS)
+                    .map(|cell| self.conflict_cell(cell, grammar, notation))
+                    .collect::<Vec<String>>()
+                    .join("; ");
+                let mut reason = match (self, cells.len()) {
+                    (Language::En, 1) => format!("1 conflict: {}", listed),
+                    (Language::En, count) => format!("{} conflicts: {}", count, listed),
+                    (Language::It, 1) => format!("1 conflitto: {}", listed),
+                    (Language::It, count) => format!("{} conflitti: {}", count, listed),
+                };
+                if cells.len() > MAX_LISTED_CONFLICTS {
+                    let more = cells.len() - MAX_LISTED_CONFLICTS;
+                    reason.push_str(&match (self, more) {
+                        (Language::En, _) => format!("; +{} more", more),
+                        (Language::It, 1) => String::from("; +1 altro"),
+                        (Language::It, _) => format!("; +{} altri", more),
+                    });
+                }
+                reason
+            }
+            (Language::En, VerdictReason::UnambiguousClass { class }) => {
+                format!("{} grammars are never ambiguous", self.class_name(*class))
+            }
+            (Language::It, VerdictReason::UnambiguousClass { class }) => {
+                format!(
+                    "le grammatiche {} non sono mai ambigue",
+                    self.class_name(*class)
+                )
+            }
+            (
+                Language::En,
+                VerdictReason::Witness {
+                    sentence,
+                    derivations,
+                },
+            ) => format!(
+                "witness {} has two leftmost derivations ({} / {})",
+                notation.body(grammar, sentence),
+                derivation(&derivations.0),
+                derivation(&derivations.1)
+            ),
+            (
+                Language::It,
+                VerdictReason::Witness {
+                    sentence,
+                    derivations,
+                },
+            ) => format!(
+                "la frase {} ha due derivazioni sinistre ({} / {})",
+                notation.body(grammar, sentence),
+                derivation(&derivations.0),
+                derivation(&derivations.1)
+            ),
+            (Language::En, VerdictReason::NoWitness { max_sentence_len }) => format!(
+                "no witness found among sentences of length up to {}",
+                max_sentence_len
+            ),
+            (Language::It, VerdictReason::NoWitness { max_sentence_len }) => format!(
+                "nessuna frase ambigua di lunghezza fino a {}",
+                max_sentence_len
+            ),
+        }
+    }
+
+    /* ######################### Generated sentences ######################### */
+
+    pub fn sentences(&self) -> &'static str {
+        match self {
+            Language::En => "Sentences",
+            Language::It => "Frasi",
+        }
+    }
+}
@@ -1,5 +1,14 @@
+pub mod classify;
 pub mod create_grammar;
+pub mod generate;
+#[allow(clippy::module_inception)]
 pub mod grammar;
 pub mod latex;
+pub mod lexer;
+pub mod locale;
+pub mod notation;
 pub mod parse_algorithms;
 pub mod parse_structs;
+pub mod precedence;
+pub mod simulate;
+pub mod transform;
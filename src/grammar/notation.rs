@@ -0,0 +1,129 @@
+use super::{
+    grammar::Grammar,
+    latex::escape_latex,
+    parse_structs::{Action, Production},
+};
+
+/// Notations used by the textbooks, selecting the defaults of `Notation`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum NotationProfile {
+    /// `ε`, `->`, `•`, states `s0`, productions numbered from 1
+    #[default]
+    Course,
+    /// `ε`, `→`, `·`, states `I0`, productions numbered from 1
+    Dragon,
+    /// `ε`, `→`, `•`, states `0`, productions numbered from 0
+    Grammophone,
+}
+
+/// How symbols, items, states and actions are written by every renderer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notation {
+    /// Written in place of an empty body
+    pub epsilon: String,
+    /// Separates the driver of a production from its body
+    pub arrow: String,
+    /// Marks the position of the dot in an item
+    pub item_dot: String,
+    /// Written before the number of a state
+    pub state_prefix: String,
+    /// Number of the first production in the reduce actions
+    pub first_production: usize,
+}
+
+impl Default for Notation {
+    fn default() -> Self {
+        Self::from_profile(NotationProfile::default())
+    }
+}
+
+impl Notation {
+    pub fn from_profile(profile: NotationProfile) -> Self {
+        let (epsilon, arrow, item_dot, state_prefix, first_production) = match profile {
+            NotationProfile::Course => ("ε", "->", "•", "s", 1),
+            NotationProfile::Dragon => ("ε", "→", "·", "I", 1),
+            NotationProfile::Grammophone => ("ε", "→", "•", "", 0),
+        };
+        Notation {
+            epsilon: epsilon.to_string(),
+            arrow: arrow.to_string(),
+            item_dot: item_dot.to_string(),
+            state_prefix: state_prefix.to_string(),
+            first_production,
+        }
+    }
+
+    pub fn state(&self, index: usize) -> String {
+        format!("{}{}", self.state_prefix, index)
+    }
+
+    /// Number of the production with the given index in `Grammar::productions`
+    pub fn production_number(&self, index: usize) -> usize {
+        index + self.first_production
+    }
+
+    pub fn action(&self, action: &Action) -> String {
+        match action {
+            Action::Reduce(index) => format!("r{}", self.production_number(*index)),
+            _ => action.to_string(),
+        }
+    }
+
+    /// Formats a body, using the epsilon for empty ones
+    pub fn body(&self, grammar: &Grammar, body: &[char]) -> String {
+        if body.is_empty() {
+            self.epsilon.clone()
+        } else {
+            grammar.format_symbols(body)
+        }
+    }
+
+    pub fn production(&self, grammar: &Grammar, production: &Production) -> String {
+        format!(
+            "{} {} {}",
+            grammar.get_symbol_name(production.driver),
+            self.arrow,
+            self.body(grammar, &production.body)
+        )
+    }
+
+    /// Formats the LR(0) item of the given production with the dot at the given position
+    pub fn item(&self, grammar: &Grammar, production: &Production, dot_position: usize) -> String {
+        let (before_dot, after_dot) = production.body.split_at(dot_position);
+        format!(
+            "{} {} {}{}{}",
+            grammar.get_symbol_name(production.driver),
+            self.arrow,
+            grammar.format_symbols(before_dot),
+            self.item_dot,
+            grammar.format_symbols(after_dot)
+        )
+    }
+
+    /// The epsilon in LaTeX math mode
+    pub fn latex_epsilon(&self) -> String {
+        match self.epsilon.as_str() {
+            "ε" => String::from("\\epsilon"),
+            "λ" => String::from("\\lambda"),
+            epsilon => format!("\\text{{{}}}", escape_latex(epsilon)),
+        }
+    }
+
+    /// The dot of the items in LaTeX math mode
+    pub fn latex_item_dot(&self) -> String {
+        match self.item_dot.as_str() {
+            "•" => String::from("\\bullet"),
+            "·" => String::from("\\cdot"),
+            item_dot => format!("\\text{{{}}}", escape_latex(item_dot)),
+        }
+    }
+
+    /// The arrow in LaTeX math mode
+    pub fn latex_arrow(&self) -> String {
+        match self.arrow.as_str() {
+            "->" | "→" => String::from("\\rightarrow"),
+            "::=" => String::from("::="),
+            arrow => format!("\\text{{{}}}", escape_latex(arrow)),
+        }
+    }
+}
@@ -7,11 +7,14 @@ use petgraph::{
     visit::EdgeRef,
 };
 
-use crate::lr0::{Lr0Item, get_parsing_automaton};
+use crate::{
+    lr0::{Lr0Automaton, Lr0Item, get_parsing_automaton},
+    lr1::{Lr1Automaton, get_lr1_parsing_automaton},
+};
 
 use super::{
     grammar::Grammar,
-    parse_structs::{Action, FirstFollowSet, Production},
+    parse_structs::{Action, FirstFollowSet, ParsingTable, ParsingTableKind, Production},
 };
 
 impl Grammar {
@@ -27,24 +30,24 @@ impl Grammar {
             let current_item = queue.pop_front().unwrap();
             let next_symbol = current_item.next_symbol();
 
-            if let Some(next_symbol) = next_symbol {
-                if !added_symbols.contains(&next_symbol) {
-                    added_symbols.insert(next_symbol);
-                    let closing_items = self
-                        .productions
-                        .iter()
-                        .filter(|prod| prod.driver == next_symbol);
-
-                    for production in closing_items {
-                        let next_item = production.as_lr0_item();
-                        queue.push_back(next_item.clone());
-                        rv.push(next_item);
-                    }
+            if let Some(next_symbol) = next_symbol
+                && !added_symbols.contains(&next_symbol)
+            {
+                added_symbols.insert(next_symbol);
+                let closing_items = self
+                    .productions
+                    .iter()
+                    .filter(|prod| prod.driver == next_symbol);
+
+                for production in closing_items {
+                    let next_item = production.as_lr0_item();
+                    queue.push_back(next_item.clone());
+                    rv.push(next_item);
                 }
             }
         }
         rv.sort_by(|lhs, rhs| {
-            let mut p_iter = self.productions.iter();
+            let p_iter = self.productions.iter();
             let lhs_pos = p_iter
                 .clone()
                 .position(|e| e.driver == lhs.production.driver)
@@ -61,26 +64,25 @@ impl Grammar {
     }
 
     pub fn get_lr0_parsing_table(&self) -> Vec<HashMap<char, Vec<Action>>> {
-        let automaton = get_parsing_automaton(self);
+        self.get_lr0_parsing_table_from(&get_parsing_automaton(self))
+    }
+
+    /// Builds the LR(0) parsing table of an already built LR(0) automaton of the grammar
+    pub fn get_lr0_parsing_table_from(
+        &self,
+        automaton: &Lr0Automaton,
+    ) -> Vec<HashMap<char, Vec<Action>>> {
         let mut rv = vec![];
         for (node_index, node) in automaton.nodes.iter().enumerate() {
-            let mut row = HashMap::new();
-
-            for term in self.terms.iter() {
-                row.insert(*term, vec![]);
-            }
-            row.insert('$', vec![]);
-            for non_term in self.non_terms.iter() {
-                row.insert(*non_term, vec![]);
-            }
+            let mut row = self.empty_parsing_table_row();
 
             // Populating shifs and gotos
             if let Some(edges) = automaton.edges.get(&node_index) {
                 for (node_to, by_char) in edges {
-                    if by_char.is_uppercase() {
-                        row.get_mut(&by_char).unwrap().push(Action::Goto(*node_to));
+                    if self.non_terms.contains(by_char) {
+                        row.get_mut(by_char).unwrap().push(Action::Goto(*node_to));
                     } else {
-                        row.get_mut(&by_char).unwrap().push(Action::Shift(*node_to));
+                        row.get_mut(by_char).unwrap().push(Action::Shift(*node_to));
                     }
                 }
             }
@@ -135,14 +137,14 @@ impl Grammar {
 
         let first_follow_owned;
         let first_follow_set = match first_follow_set {
-            Some(ref s) => s,
+            Some(s) => s,
             None => {
                 first_follow_owned = self.get_first_follow_table();
                 &first_follow_owned
             }
         };
 
-        for (node_index, row) in parsing_table.iter_mut().enumerate() {
+        for row in parsing_table.iter_mut() {
             for (by_char, actions) in row.iter_mut() {
                 actions.retain(|a| match a {
                     Action::Reduce(reduce) => first_follow_set
@@ -158,6 +160,274 @@ impl Grammar {
         parsing_table
     }
 
+    /// Builds the canonical LR(1) parsing table. Rows are indexed by the states of the LR(1)
+    /// automaton, which generally are more than the LR(0) ones.
+    pub fn get_lr1_parsing_table(
+        &self,
+        first_follow_set: Option<&HashMap<char, FirstFollowSet>>,
+    ) -> Vec<HashMap<char, Vec<Action>>> {
+        let first_follow_owned;
+        let first_follow_set = match first_follow_set {
+            Some(s) => s,
+            None => {
+                first_follow_owned = self.get_first_follow_table();
+                &first_follow_owned
+            }
+        };
+
+        self.get_lr1_parsing_table_from(&get_lr1_parsing_automaton(self, first_follow_set))
+    }
+
+    /// Builds the canonical LR(1) parsing table of an already built LR(1) automaton of the
+    /// grammar
+    pub fn get_lr1_parsing_table_from(
+        &self,
+        automaton: &Lr1Automaton,
+    ) -> Vec<HashMap<char, Vec<Action>>> {
+        let mut rv = vec![];
+        for (node_index, node) in automaton.nodes.iter().enumerate() {
+            let mut row = self.empty_parsing_table_row();
+
+            if let Some(edges) = automaton.edges.get(&node_index) {
+                for (node_to, by_char) in edges {
+                    if self.non_terms.contains(by_char) {
+                        row.get_mut(by_char).unwrap().push(Action::Goto(*node_to));
+                    } else {
+                        row.get_mut(by_char).unwrap().push(Action::Shift(*node_to));
+                    }
+                }
+            }
+
+            for item in node.kernel.iter().chain(node.closure.iter()) {
+                if item.is_complete() {
+                    let action = match item.production.index {
+                        Some(prod_index) => Action::Reduce(prod_index),
+                        None => Action::Acc,
+                    };
+                    row.get_mut(&item.lookahead).unwrap().push(action);
+                }
+            }
+
+            rv.push(row);
+        }
+
+        rv
+    }
+
+    /// Builds the LALR(1) parsing table by merging the LR(1) states having the same LR(0) core.
+    /// Rows are indexed by the states of the LR(0) automaton, so that the table can be directly
+    /// compared with the LR(0) and SLR(1) ones.
+    pub fn get_lalr1_parsing_table(
+        &self,
+        parsing_table: Option<&Vec<HashMap<char, Vec<Action>>>>,
+        first_follow_set: Option<&HashMap<char, FirstFollowSet>>,
+    ) -> Vec<HashMap<char, Vec<Action>>> {
+        let mut parsing_table = match parsing_table {
+            Some(t) => t.clone(),
+            None => self.get_lr0_parsing_table(),
+        };
+
+        let first_follow_owned;
+        let first_follow_set = match first_follow_set {
+            Some(s) => s,
+            None => {
+                first_follow_owned = self.get_first_follow_table();
+                &first_follow_owned
+            }
+        };
+
+        let lr0_automaton = get_parsing_automaton(self);
+        let lr1_automaton = get_lr1_parsing_automaton(self, first_follow_set);
+
+        let mut lr0_node_by_core: HashMap<Vec<(Option<usize>, usize)>, usize> = HashMap::new();
+        for (node_index, node) in lr0_automaton.nodes.iter().enumerate() {
+            let mut core: Vec<(Option<usize>, usize)> = node
+                .kernel
+                .iter()
+                .map(|item| (item.production.index, item.dot_position()))
+                .collect();
+            core.sort();
+            core.dedup();
+            lr0_node_by_core.insert(core, node_index);
+        }
+
+        // Lookaheads of every reduce action, merged over the LR(1) states sharing the same core
+        let mut lookaheads: HashMap<(usize, usize), HashSet<char>> = HashMap::new();
+        for node in lr1_automaton.nodes.iter() {
+            let lr0_node_index = *lr0_node_by_core.get(&node.kernel_core()).expect(
+                "every LR(1) state has an LR(0) twin when all the non terminals are productive",
+            );
+            for item in node.kernel.iter().chain(node.closure.iter()) {
+                if let (true, Some(prod_index)) = (item.is_complete(), item.production.index) {
+                    lookaheads
+                        .entry((lr0_node_index, prod_index))
+                        .or_default()
+                        .insert(item.lookahead);
+                }
+            }
+        }
+
+        for (node_index, row) in parsing_table.iter_mut().enumerate() {
+            for (by_char, actions) in row.iter_mut() {
+                actions.retain(|a| match a {
+                    Action::Reduce(reduce) => lookaheads
+                        .get(&(node_index, *reduce))
+                        .is_some_and(|set| set.contains(by_char)),
+                    _ => true,
+                });
+            }
+        }
+
+        parsing_table
+    }
+
+    /// Builds the LL(1) parsing table: for each non terminal and each terminal (or `$`) the
+    /// indices of the productions to be expanded. Cells with more than one production are
+    /// conflicts.
+    pub fn get_ll1_parsing_table(
+        &self,
+        first_follow_set: Option<&HashMap<char, FirstFollowSet>>,
+    ) -> HashMap<char, HashMap<char, Vec<usize>>> {
+        let first_follow_owned;
+        let first_follow_set = match first_follow_set {
+            Some(s) => s,
+            None => {
+                first_follow_owned = self.get_first_follow_table();
+                &first_follow_owned
+            }
+        };
+
+        let mut table: HashMap<char, HashMap<char, Vec<usize>>> = HashMap::new();
+        for non_term in self.non_terms.iter() {
+            let mut row = HashMap::new();
+            for term in self.terms.iter() {
+                row.insert(*term, vec![]);
+            }
+            row.insert('$', vec![]);
+            table.insert(*non_term, row);
+        }
+
+        for (prod_index, production) in self.productions.iter().enumerate() {
+            let (first, nullable) = self.first_of_sequence(&production.body, first_follow_set);
+            let mut lookaheads = first;
+            if nullable {
+                lookaheads.extend(
+                    first_follow_set
+                        .get(&production.driver)
+                        .unwrap()
+                        .follow
+                        .iter(),
+                );
+            }
+
+            let row = table.get_mut(&production.driver).unwrap();
+            for lookahead in lookaheads {
+                row.get_mut(&lookahead).unwrap().push(prod_index);
+            }
+        }
+
+        table
+    }
+
+    /// Returns whether the item of `production` with the dot at `dot_position` takes part in a
+    /// conflict of the given row of an LR parsing table: a complete item if its reduce (or the
+    /// accept of the augmented production) shares a cell with other actions, any other item if
+    /// the shift by its next symbol does.
+    pub fn is_conflicting_item(
+        &self,
+        row: &HashMap<char, Vec<Action>>,
+        production: &Production,
+        dot_position: usize,
+    ) -> bool {
+        match production.body.get(dot_position) {
+            Some(next_symbol) => row.get(next_symbol).is_some_and(|actions| {
+                actions.len() > 1
+                    && actions
+                        .iter()
+                        .any(|action| matches!(action, Action::Shift(_)))
+            }),
+            None => {
+                let action = match production.index {
+                    Some(prod_index) => Action::Reduce(prod_index),
+                    None => Action::Acc,
+                };
+                row.values()
+                    .any(|actions| actions.len() > 1 && actions.contains(&action))
+            }
+        }
+    }
+
+    /// Builds the parsing table of the given kind. The shift/reduce conflicts of the SLR(1),
+    /// LALR(1) and LR(1) tables are solved with the precedence declarations, if any.
+    pub fn get_parsing_table(
+        &self,
+        kind: ParsingTableKind,
+        first_follow_set: Option<&HashMap<char, FirstFollowSet>>,
+    ) -> ParsingTable {
+        let first_follow_owned;
+        let first_follow_set = match first_follow_set {
+            Some(s) => s,
+            None => {
+                first_follow_owned = self.get_first_follow_table();
+                &first_follow_owned
+            }
+        };
+
+        let mut rows = match kind {
+            ParsingTableKind::Ll1 => {
+                return ParsingTable::Ll1(self.get_ll1_parsing_table(Some(first_follow_set)));
+            }
+            ParsingTableKind::Lr0 => {
+                return ParsingTable::Lr {
+                    rows: self.get_lr0_parsing_table(),
+                    resolutions: vec![],
+                };
+            }
+            ParsingTableKind::Slr1 => self.get_slr1_parsing_table(None, Some(first_follow_set)),
+            ParsingTableKind::Lalr1 => self.get_lalr1_parsing_table(None, Some(first_follow_set)),
+            ParsingTableKind::Lr1 => self.get_lr1_parsing_table(Some(first_follow_set)),
+        };
+        let resolutions = self.resolve_conflicts(&mut rows);
+        ParsingTable::Lr { rows, resolutions }
+    }
+
+    /// Returns the FIRST set of a sequence of symbols and whether the whole sequence is nullable.
+    /// Symbols which are not keys of `first_follow_set` are treated as terminals.
+    pub fn first_of_sequence(
+        &self,
+        symbols: &[char],
+        first_follow_set: &HashMap<char, FirstFollowSet>,
+    ) -> (HashSet<char>, bool) {
+        let mut first = HashSet::new();
+        for symbol in symbols {
+            match first_follow_set.get(symbol) {
+                Some(set) => {
+                    first.extend(set.first.iter());
+                    if !set.nullable {
+                        return (first, false);
+                    }
+                }
+                None => {
+                    first.insert(*symbol);
+                    return (first, false);
+                }
+            }
+        }
+        (first, true)
+    }
+
+    fn empty_parsing_table_row(&self) -> HashMap<char, Vec<Action>> {
+        let mut row = HashMap::new();
+        for term in self.terms.iter() {
+            row.insert(*term, vec![]);
+        }
+        row.insert('$', vec![]);
+        for non_term in self.non_terms.iter() {
+            row.insert(*non_term, vec![]);
+        }
+        row
+    }
+
     /// Creates a table containing for each non terminal
     /// 1) the first set
     /// 2) the follow set
@@ -203,22 +473,25 @@ impl Grammar {
         /* ######################### FIRST ######################### */
         let mut productions_by_driver: HashMap<char, Vec<&Production>> = HashMap::new();
         for production in &self.productions {
-            if !productions_by_driver.contains_key(&production.driver) {
-                productions_by_driver.insert(production.driver, vec![]);
-            }
             productions_by_driver
-                .get_mut(&production.driver)
-                .unwrap()
+                .entry(production.driver)
+                .or_default()
                 .push(production);
         }
 
         for (driver, production_set) in &productions_by_driver {
-            let mut curr_first_set = &mut first_follow_table.get_mut(&driver).unwrap().first;
+            let curr_first_set = &mut first_follow_table.get_mut(driver).unwrap().first;
 
             for prod in production_set {
-                if let Some(first_terminal) = prod.body.iter().find(|symbol| symbol.is_lowercase())
-                {
-                    curr_first_set.insert(*first_terminal);
+                // Only terminals preceded by nullable non terminals belong to the first set
+                for symbol in &prod.body {
+                    if !self.non_terms.contains(symbol) {
+                        curr_first_set.insert(*symbol);
+                        break;
+                    }
+                    if !nullables.contains(symbol) {
+                        break;
+                    }
                 }
             }
         }
@@ -236,13 +509,13 @@ impl Grammar {
 
         for prod in &self.productions {
             for symbol in &prod.body {
-                if symbol.is_uppercase() {
+                if self.non_terms.contains(symbol) {
                     let node_from_idx = first_graph_node_indices.get(&prod.driver).unwrap();
-                    let node_to_idx = first_graph_node_indices.get(&symbol).unwrap();
+                    let node_to_idx = first_graph_node_indices.get(symbol).unwrap();
                     first_graph.add_edge(*node_from_idx, *node_to_idx, ());
                 }
 
-                if symbol.is_lowercase() || !nullables.contains(&symbol) {
+                if !self.non_terms.contains(symbol) || !nullables.contains(symbol) {
                     break;
                 }
             }
@@ -267,12 +540,12 @@ impl Grammar {
         }
 
         for prod in self.productions.iter() {
-            if prod.body.len() == 0 {
+            if prod.body.is_empty() {
                 continue;
             }
             for i in 0..prod.body.len() {
                 let l_char = prod.body[i];
-                if l_char.is_lowercase() {
+                if !self.non_terms.contains(&l_char) {
                     continue;
                 }
 
@@ -281,7 +554,7 @@ impl Grammar {
                 let mut body_nullable = true;
                 for j in i + 1..prod.body.len() {
                     let r_char = prod.body[j];
-                    if r_char.is_uppercase() {
+                    if self.non_terms.contains(&r_char) {
                         new_follows.extend(
                             first_follow_table
                                 .get(&r_char)
@@ -310,19 +583,20 @@ impl Grammar {
                     .node_weight_mut(*follow_graph_node_indices.get(&l_char).unwrap())
                     .unwrap()
                     .1
-                    .extend(new_follows.into_iter());
+                    .extend(new_follows);
             }
         }
 
-        follow_graph
-            .node_weight_mut(
-                *follow_graph_node_indices
-                    .get(&self.productions[0].driver)
-                    .unwrap(),
-            )
-            .unwrap()
-            .1
-            .insert('$');
+        if let Some(start_node) = self
+            .get_start_symbol()
+            .and_then(|start_symbol| follow_graph_node_indices.get(&start_symbol))
+        {
+            follow_graph
+                .node_weight_mut(*start_node)
+                .unwrap()
+                .1
+                .insert('$');
+        }
         let follow_condensation_graph = Self::propagate_referece_graph(&follow_graph);
         for (symbols, follows) in follow_condensation_graph.node_weights() {
             for non_term in symbols.iter() {
@@ -342,7 +616,7 @@ impl Grammar {
     fn propagate_referece_graph(
         graph: &Graph<(char, HashSet<char>), ()>,
     ) -> Graph<(HashSet<char>, HashSet<char>), ()> {
-        let mut condensation_graph = condensation(graph.clone(), true);
+        let condensation_graph = condensation(graph.clone(), true);
 
         let mut first_by_condensation_node: HashMap<NodeIndex, HashSet<char>> = HashMap::new();
 
@@ -363,15 +637,11 @@ impl Grammar {
         let mut condensation_graph = condensation_graph.map(
             |idx, wheight| {
                 (
-                    wheight
-                        .iter()
-                        .cloned()
-                        .map(|(c, _)| c)
-                        .collect::<HashSet<char>>(),
+                    wheight.iter().map(|(c, _)| *c).collect::<HashSet<char>>(),
                     first_by_condensation_node.remove(&idx).unwrap(),
                 )
             },
-            |idx, wheight| *wheight,
+            |_idx, wheight| *wheight,
         );
 
         let topological_order =
@@ -399,3 +669,36 @@ impl Grammar {
         condensation_graph
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::grammar::{create_grammar::create_grammar_from_str, parse_structs::Action};
+
+    #[test]
+    fn symbols_other_than_upper_case_letters_are_terminals() {
+        let grammar = create_grammar_from_str(&String::from("S -> ( S ) S | + .")).unwrap();
+        assert_eq!(grammar.terms, HashSet::from(['(', ')', '+']));
+
+        // The rows have a column for `(`, which the initial state shifts
+        let parsing_table = grammar.get_lr0_parsing_table();
+        assert!(matches!(parsing_table[0][&'('][..], [Action::Shift(_)]));
+    }
+
+    #[test]
+    fn first_sets_skip_only_nullable_non_terminals() {
+        // `b` follows a non nullable non terminal, so it is not in FIRST(S)
+        let grammar = create_grammar_from_str(&String::from("S -> A b .\nA -> a .")).unwrap();
+        let first_follow_set = grammar.get_first_follow_table();
+        assert_eq!(first_follow_set[&'S'].first, HashSet::from(['a']));
+
+        let grammar = create_grammar_from_str(&String::from("S -> A b .\nA -> a | .")).unwrap();
+        let first_follow_set = grammar.get_first_follow_table();
+        assert_eq!(first_follow_set[&'S'].first, HashSet::from(['a', 'b']));
+
+        let grammar = create_grammar_from_str(&String::from("S -> ( S ) S | + .")).unwrap();
+        let first_follow_set = grammar.get_first_follow_table();
+        assert_eq!(first_follow_set[&'S'].first, HashSet::from(['(', '+']));
+    }
+}
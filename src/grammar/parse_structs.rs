@@ -1,12 +1,19 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
 
 use crate::lr0::Lr0Item;
+use crate::util::{serialize_sorted_maps, serialize_sorted_nested_map, serialize_sorted_set};
+
+use super::precedence::ConflictResolution;
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct Production {
     pub index: Option<usize>,
     pub driver: char,
     pub body: Vec<char>,
+    /// Terminal whose precedence is explicitly assigned to the production with `%prec`
+    pub precedence: Option<char>,
 }
 
 impl Production {
@@ -15,6 +22,7 @@ impl Production {
             index: None,
             driver,
             body,
+            precedence: None,
         }
     }
 
@@ -23,13 +31,36 @@ impl Production {
     }
 }
 
-#[derive(Debug, Clone)]
+impl std::fmt::Display for Production {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.body.is_empty() {
+            write!(f, "{} -> ε", self.driver)
+        } else {
+            write!(
+                f,
+                "{} -> {}",
+                self.driver,
+                self.body.iter().collect::<String>()
+            )
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FirstFollowSet {
+    #[serde(serialize_with = "serialize_sorted_set")]
     pub first: HashSet<char>,
+    #[serde(serialize_with = "serialize_sorted_set")]
     pub follow: HashSet<char>,
     pub nullable: bool,
 }
 
+impl Default for FirstFollowSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl FirstFollowSet {
     pub fn new() -> Self {
         FirstFollowSet {
@@ -40,10 +71,14 @@ impl FirstFollowSet {
     }
 }
 
-#[derive(Debug, Clone)]
+/// An entry of an LR parsing table. The index of `Reduce` is the 0 based index of the production
+/// in `Grammar::productions`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", content = "value", rename_all = "snake_case")]
 pub enum Action {
     Shift(usize),
     Reduce(usize),
+    #[serde(rename = "accept")]
     Acc,
     Goto(usize),
 }
@@ -58,3 +93,43 @@ impl std::fmt::Display for Action {
         }
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, clap::ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParsingTableKind {
+    Ll1,
+    Lr0,
+    Slr1,
+    Lalr1,
+    Lr1,
+}
+
+impl std::fmt::Display for ParsingTableKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParsingTableKind::Ll1 => write!(f, "LL(1)"),
+            ParsingTableKind::Lr0 => write!(f, "LR(0)"),
+            ParsingTableKind::Slr1 => write!(f, "SLR(1)"),
+            ParsingTableKind::Lalr1 => write!(f, "LALR(1)"),
+            ParsingTableKind::Lr1 => write!(f, "LR(1)"),
+        }
+    }
+}
+
+/// A parsing table, which doesn't borrow the grammar: symbols are stored as their character and
+/// productions by their index
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum ParsingTable {
+    /// For each non terminal and each terminal (or `$`) the indices of the productions to expand
+    Ll1(
+        #[serde(serialize_with = "serialize_sorted_nested_map")]
+        HashMap<char, HashMap<char, Vec<usize>>>,
+    ),
+    /// One row per automaton state, together with the conflicts solved by precedence
+    Lr {
+        #[serde(serialize_with = "serialize_sorted_maps")]
+        rows: Vec<HashMap<char, Vec<Action>>>,
+        resolutions: Vec<ConflictResolution>,
+    },
+}
@@ -0,0 +1,331 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{grammar::Grammar, parse_structs::Action};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Associativity {
+    Left,
+    Right,
+    NonAssoc,
+}
+
+/// Precedence of a terminal. Terminals declared later have higher `level`, as in yacc.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Precedence {
+    pub level: usize,
+    pub associativity: Associativity,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResolutionReason {
+    /// The production has higher precedence than the lookahead: reduce
+    HigherPrecedence,
+    /// The production has lower precedence than the lookahead: shift
+    LowerPrecedence,
+    /// Same precedence, left associative: reduce
+    LeftAssociative,
+    /// Same precedence, right associative: shift
+    RightAssociative,
+    /// Same precedence, non associative: the cell becomes an error entry
+    NonAssociative,
+}
+
+impl std::fmt::Display for ResolutionReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResolutionReason::HigherPrecedence => write!(f, "production has higher precedence"),
+            ResolutionReason::LowerPrecedence => write!(f, "lookahead has higher precedence"),
+            ResolutionReason::LeftAssociative => write!(f, "left associative"),
+            ResolutionReason::RightAssociative => write!(f, "right associative"),
+            ResolutionReason::NonAssociative => write!(f, "non associative"),
+        }
+    }
+}
+
+/// A conflict solved using the precedence declarations. `kept` is `None` when the cell was
+/// turned into an error entry by `%nonassoc`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConflictResolution {
+    pub state: usize,
+    pub symbol: char,
+    pub kept: Option<Action>,
+    pub discarded: Vec<Action>,
+    pub reason: ResolutionReason,
+}
+
+impl Grammar {
+    /// Returns the precedence of the production with the given index: the one of the `%prec`
+    /// terminal if specified, of the rightmost terminal of the body otherwise.
+    pub fn get_production_precedence(&self, prod_index: usize) -> Option<Precedence> {
+        let production = &self.productions[prod_index];
+        let symbol = match production.precedence {
+            Some(symbol) => Some(symbol),
+            None => production
+                .body
+                .iter()
+                .rev()
+                .find(|symbol| !self.non_terms.contains(symbol))
+                .copied(),
+        }?;
+        self.precedences.get(&symbol).copied()
+    }
+
+    /// Solves the shift/reduce conflicts of the given parsing table the way yacc does, comparing
+    /// the precedence of the production with the one of the lookahead. When the shift clashes
+    /// with several reduces, the production listed first in the grammar is the one compared and
+    /// kept, as in yacc.
+    ///
+    /// Reduce/reduce conflicts and cells holding the accept action are left untouched, and so
+    /// is, unlike yacc which shifts by default, a cell whose production or lookahead has no
+    /// precedence, so that the conflict stays visible.
+    pub fn resolve_conflicts(
+        &self,
+        parsing_table: &mut [HashMap<char, Vec<Action>>],
+    ) -> Vec<ConflictResolution> {
+        let mut resolutions = vec![];
+        let mut sorted_terms = self.get_sorted_terms();
+        sorted_terms.push('$');
+
+        for (node_index, row) in parsing_table.iter_mut().enumerate() {
+            for term in sorted_terms.iter() {
+                let Some(actions) = row.get_mut(term) else {
+                    continue;
+                };
+                if actions.len() < 2 || actions.contains(&Action::Acc) {
+                    continue;
+                }
+                let Some(shift) = actions.iter().find_map(|action| match action {
+                    Action::Shift(to) => Some(*to),
+                    _ => None,
+                }) else {
+                    continue;
+                };
+                let Some(reduce) = actions
+                    .iter()
+                    .filter_map(|action| match action {
+                        Action::Reduce(prod_index) => Some(*prod_index),
+                        _ => None,
+                    })
+                    .min()
+                else {
+                    continue;
+                };
+
+                let (Some(prod_precedence), Some(term_precedence)) = (
+                    self.get_production_precedence(reduce),
+                    self.precedences.get(term),
+                ) else {
+                    continue;
+                };
+                let reason = Self::compare_precedences(prod_precedence, *term_precedence);
+                let kept = match reason {
+                    ResolutionReason::HigherPrecedence | ResolutionReason::LeftAssociative => {
+                        Some(Action::Reduce(reduce))
+                    }
+                    ResolutionReason::LowerPrecedence | ResolutionReason::RightAssociative => {
+                        Some(Action::Shift(shift))
+                    }
+                    ResolutionReason::NonAssociative => None,
+                };
+
+                let discarded = actions
+                    .iter()
+                    .filter(|action| Some(*action) != kept.as_ref())
+                    .cloned()
+                    .collect();
+                *actions = kept.iter().cloned().collect();
+
+                resolutions.push(ConflictResolution {
+                    state: node_index,
+                    symbol: *term,
+                    kept,
+                    discarded,
+                    reason,
+                });
+            }
+        }
+
+        resolutions
+    }
+
+    /// Chooses between reducing a production and shifting a lookahead with the given
+    /// precedences
+    fn compare_precedences(
+        prod_precedence: Precedence,
+        term_precedence: Precedence,
+    ) -> ResolutionReason {
+        if prod_precedence.level > term_precedence.level {
+            ResolutionReason::HigherPrecedence
+        } else if prod_precedence.level < term_precedence.level {
+            ResolutionReason::LowerPrecedence
+        } else {
+            match term_precedence.associativity {
+                Associativity::Left => ResolutionReason::LeftAssociative,
+                Associativity::Right => ResolutionReason::RightAssociative,
+                Associativity::NonAssoc => ResolutionReason::NonAssociative,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grammar::{
+        create_grammar::create_grammar_from_str,
+        parse_structs::{ParsingTable, ParsingTableKind},
+    };
+
+    /// The resolutions of the SLR(1) table of the grammar on the given lookahead
+    fn resolutions_on(definition: &str, symbol: char) -> Vec<ConflictResolution> {
+        let grammar = create_grammar_from_str(definition).unwrap();
+        let ParsingTable::Lr { rows, resolutions } =
+            grammar.get_parsing_table(ParsingTableKind::Slr1, None)
+        else {
+            unreachable!();
+        };
+        let resolutions: Vec<ConflictResolution> = resolutions
+            .into_iter()
+            .filter(|resolution| resolution.symbol == symbol)
+            .collect();
+        for resolution in resolutions.iter() {
+            let cell = rows[resolution.state]
+                .get(&symbol)
+                .cloned()
+                .unwrap_or_default();
+            assert_eq!(
+                cell,
+                resolution.kept.iter().cloned().collect::<Vec<Action>>()
+            );
+        }
+        resolutions
+    }
+
+    #[test]
+    fn left_associative_operators_reduce() {
+        let resolutions = resolutions_on("%left '+'\nE -> E + E | a .", '+');
+        assert_eq!(resolutions.len(), 1);
+        assert_eq!(resolutions[0].kept, Some(Action::Reduce(0)));
+        assert_eq!(resolutions[0].reason, ResolutionReason::LeftAssociative);
+    }
+
+    #[test]
+    fn right_associative_operators_shift() {
+        let resolutions = resolutions_on("%right '^'\nE -> E ^ E | a .", '^');
+        assert_eq!(resolutions.len(), 1);
+        assert!(matches!(resolutions[0].kept, Some(Action::Shift(_))));
+        assert_eq!(resolutions[0].discarded, vec![Action::Reduce(0)]);
+        assert_eq!(resolutions[0].reason, ResolutionReason::RightAssociative);
+    }
+
+    #[test]
+    fn non_associative_operators_become_errors() {
+        let resolutions = resolutions_on("%nonassoc '<'\nE -> E < E | a .", '<');
+        assert_eq!(resolutions.len(), 1);
+        assert_eq!(resolutions[0].kept, None);
+        assert_eq!(resolutions[0].discarded.len(), 2);
+        assert_eq!(resolutions[0].reason, ResolutionReason::NonAssociative);
+    }
+
+    #[test]
+    fn prec_overrides_the_precedence_of_the_production() {
+        // The unary minus takes the precedence of `u`, higher than the one of `*`
+        let definition = "%left '-'\n%left '*'\n%right u\nE -> E - E | E * E | - E %prec u | a .";
+        let grammar = create_grammar_from_str(definition).unwrap();
+        assert_eq!(grammar.get_production_precedence(2).unwrap().level, 3);
+
+        let resolutions = resolutions_on(definition, '*');
+        let unary_minus = resolutions
+            .iter()
+            .find(|resolution| {
+                resolution
+                    .discarded
+                    .iter()
+                    .any(|a| matches!(a, Action::Shift(_)))
+            })
+            .filter(|resolution| resolution.kept == Some(Action::Reduce(2)))
+            .unwrap();
+        assert_eq!(unary_minus.reason, ResolutionReason::HigherPrecedence);
+
+        // Without `%prec` the production takes the precedence of `-`, so `*` is shifted
+        let resolutions =
+            resolutions_on("%left '-'\n%left '*'\nE -> E - E | E * E | - E | a .", '*');
+        assert!(resolutions.iter().any(|resolution| {
+            resolution.discarded == vec![Action::Reduce(2)]
+                && resolution.reason == ResolutionReason::LowerPrecedence
+        }));
+    }
+
+    fn resolve_cell(definition: &str, cell: Vec<Action>) -> (Vec<Action>, Vec<ConflictResolution>) {
+        let grammar = create_grammar_from_str(definition).unwrap();
+        let mut table = vec![HashMap::from([('+', cell)])];
+        let resolutions = grammar.resolve_conflicts(&mut table);
+        (table[0][&'+'].clone(), resolutions)
+    }
+
+    #[test]
+    fn shift_with_several_reduces_keeps_the_earliest_production() {
+        let definition = "%left '+'\nS -> A | B . A -> a + b | a . B -> a + c | a .";
+        let (cell, resolutions) = resolve_cell(
+            definition,
+            vec![Action::Shift(7), Action::Reduce(5), Action::Reduce(3)],
+        );
+        // `A -> a` (3) is listed before `B -> a` (5), and has no precedence
+        assert_eq!(cell.len(), 3);
+        assert!(resolutions.is_empty());
+
+        let definition = "%left '+'\nS -> A | B . A -> a + b | a '+' . B -> a + c | a .";
+        let (cell, resolutions) = resolve_cell(
+            definition,
+            vec![Action::Shift(7), Action::Reduce(5), Action::Reduce(3)],
+        );
+        assert_eq!(cell, vec![Action::Reduce(3)]);
+        assert_eq!(
+            resolutions[0].discarded,
+            vec![Action::Shift(7), Action::Reduce(5)]
+        );
+        assert_eq!(resolutions[0].reason, ResolutionReason::LeftAssociative);
+    }
+
+    #[test]
+    fn reduce_reduce_conflicts_are_kept() {
+        // Both productions have the precedence of `+`, which only settles shift/reduce conflicts
+        let definition = "%left '+'\nS -> A | B . A -> a '+' . B -> a '+' .";
+        let (cell, resolutions) =
+            resolve_cell(definition, vec![Action::Reduce(3), Action::Reduce(2)]);
+        assert_eq!(cell, vec![Action::Reduce(3), Action::Reduce(2)]);
+        assert!(resolutions.is_empty());
+    }
+
+    #[test]
+    fn accept_is_never_discarded() {
+        // The reduce of `A -> a` has the precedence of `a`, but shares the `$` cell with accept
+        let grammar = create_grammar_from_str("%left a\nS -> A . A -> S | a .").unwrap();
+        let ParsingTable::Lr { rows, resolutions } =
+            grammar.get_parsing_table(ParsingTableKind::Slr1, None)
+        else {
+            unreachable!();
+        };
+        assert!(resolutions.is_empty());
+        assert!(
+            rows.iter()
+                .any(|row| row[&'$'].contains(&Action::Acc) && row[&'$'].len() == 2)
+        );
+    }
+
+    #[test]
+    fn conflicts_are_kept_without_precedences() {
+        let grammar = create_grammar_from_str("E -> E + E | a .").unwrap();
+        let ParsingTable::Lr { rows, resolutions } =
+            grammar.get_parsing_table(ParsingTableKind::Slr1, None)
+        else {
+            unreachable!();
+        };
+        assert!(resolutions.is_empty());
+        assert!(rows.iter().any(|row| row[&'+'].len() == 2));
+    }
+}
@@ -0,0 +1,564 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use super::{
+    classify::ConflictCell,
+    grammar::Grammar,
+    latex::escape_latex,
+    locale::Language,
+    notation::Notation,
+    parse_structs::{Action, ParsingTable, ParsingTableKind},
+};
+
+/// Maximum number of steps of a simulation, guarding against grammars whose tables make the
+/// parser loop (e.g. reducing cyclic productions forever)
+const MAX_PARSE_STEPS: usize = 10_000;
+
+/// An entry of the stack of the parser: LR parsers alternate states and symbols, starting
+/// from state 0, LL(1) parsers only hold symbols
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum StackEntry {
+    State(usize),
+    Symbol(char),
+}
+
+impl StackEntry {
+    pub fn format(&self, grammar: &Grammar, notation: &Notation) -> String {
+        match self {
+            StackEntry::State(state) => notation.state(*state),
+            StackEntry::Symbol(symbol) => grammar.get_symbol_name(*symbol),
+        }
+    }
+}
+
+/// The move of the parser at a step. Productions are referred to by their index in
+/// `Grammar::productions`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "action", content = "value", rename_all = "snake_case")]
+pub enum TraceAction {
+    /// The non terminal on top of the stack is replaced by the body of the production
+    Expand(usize),
+    /// The terminal on top of the stack is the lookahead, which is consumed
+    Match(char),
+    Shift(usize),
+    Reduce(usize),
+    Accept,
+    Error,
+    Conflict,
+}
+
+impl TraceAction {
+    /// The action in English
+    pub fn format(&self, grammar: &Grammar, notation: &Notation) -> String {
+        Language::En.trace_action(self, grammar, notation)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ParseStep {
+    /// Content of the stack, bottom first
+    pub stack: Vec<StackEntry>,
+    /// Symbols still to be read, `$` included
+    pub input: Vec<char>,
+    pub action: TraceAction,
+}
+
+impl ParseStep {
+    pub fn format_stack(&self, grammar: &Grammar, notation: &Notation) -> String {
+        self.stack
+            .iter()
+            .map(|entry| entry.format(grammar, notation))
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    pub fn format_input(&self, grammar: &Grammar) -> String {
+        self.input
+            .iter()
+            .map(|symbol| grammar.get_symbol_name(*symbol))
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+}
+
+/// Why the parser stopped without accepting the sentence
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Rejection {
+    /// A word of the sentence which is not a terminal of the grammar
+    UnknownTerminal {
+        word: String,
+    },
+    NoProductions,
+    /// The terminal on top of the LL(1) stack is not the lookahead
+    Mismatch {
+        expected: char,
+        found: char,
+    },
+    /// The cell of the LL(1) table for the non terminal and the lookahead is empty
+    NoProduction {
+        non_term: char,
+        lookahead: char,
+    },
+    /// The cell of the LR table for the state and the lookahead is empty
+    NoAction {
+        state: usize,
+        lookahead: char,
+    },
+    NoGoto {
+        state: usize,
+        non_term: char,
+    },
+    TooManySteps {
+        max_steps: usize,
+    },
+}
+
+impl Rejection {
+    /// The reason of the rejection in English
+    pub fn format(&self, grammar: &Grammar, notation: &Notation) -> String {
+        Language::En.rejection(self, grammar, notation)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "result", content = "reason", rename_all = "snake_case")]
+pub enum ParseOutcome {
+    Accepted,
+    Rejected(Rejection),
+    /// The table has more than one entry in the current cell, so the parser can't go on
+    Conflict(ConflictCell),
+}
+
+impl ParseOutcome {
+    /// The outcome in English
+    pub fn format(&self, grammar: &Grammar, notation: &Notation) -> String {
+        Language::En.parse_outcome(self, grammar, notation)
+    }
+}
+
+/// A node of the parse tree, whose children are the body of the production it was expanded
+/// (or reduced) with. Terminals and non terminals derived by empty productions have none.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ParseTree {
+    pub symbol: char,
+    pub children: Vec<ParseTree>,
+}
+
+impl ParseTree {
+    fn leaf(symbol: char) -> ParseTree {
+        ParseTree {
+            symbol,
+            children: vec![],
+        }
+    }
+
+    /// Builds the tree of a leftmost derivation of the symbol, consuming its productions
+    fn from_leftmost_derivation(
+        grammar: &Grammar,
+        symbol: char,
+        derivation: &mut impl Iterator<Item = usize>,
+    ) -> ParseTree {
+        if !grammar.non_terms.contains(&symbol) {
+            return ParseTree::leaf(symbol);
+        }
+        let Some(prod_index) = derivation.next() else {
+            return ParseTree::leaf(symbol);
+        };
+        ParseTree {
+            symbol,
+            children: grammar.productions[prod_index]
+                .body
+                .iter()
+                .map(|child| ParseTree::from_leftmost_derivation(grammar, *child, derivation))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ParseTrace {
+    pub kind: ParsingTableKind,
+    /// The parsed sentence, as written by the user
+    pub sentence: String,
+    pub steps: Vec<ParseStep>,
+    pub outcome: ParseOutcome,
+    /// The parse tree of the sentence, when accepted
+    pub tree: Option<ParseTree>,
+}
+
+impl ParseTrace {
+    pub fn generate_latex_string(
+        &self,
+        grammar: &Grammar,
+        notation: &Notation,
+        language: Language,
+    ) -> String {
+        let mut rv = String::new();
+
+        rv.push_str("\\begin{table}[H]");
+        rv.push_str("\\centering");
+        rv.push_str("\\begin{tabular}{rlrl}\n");
+        rv.push_str("\\toprule\n");
+        rv.push_str(format!("{}\\\\\n", language.trace_headers().join(" & ")).as_str());
+        rv.push_str("\\midrule\n");
+        for (index, step) in self.steps.iter().enumerate() {
+            rv.push_str(
+                format!(
+                    "{} & {} & {} & {}\\\\\n",
+                    index + 1,
+                    escape_latex(&step.format_stack(grammar, notation)),
+                    escape_latex(&step.format_input(grammar)),
+                    escape_latex(&language.trace_action(&step.action, grammar, notation))
+                )
+                .as_str(),
+            );
+        }
+        rv.push_str("\\bottomrule\n");
+        rv.push_str("\\end{tabular}\n");
+        rv.push_str(
+            format!(
+                "\\caption{{{}: {}}}",
+                language.parsing_of(
+                    self.kind,
+                    &format!("\\texttt{{{}}}", escape_latex(&self.sentence))
+                ),
+                escape_latex(&language.parse_outcome(&self.outcome, grammar, notation))
+            )
+            .as_str(),
+        );
+        rv.push_str("\\label{tab:parse-trace}");
+        rv.push_str("\\end{table}");
+        rv
+    }
+
+    /// The steps in aligned columns, followed by the outcome
+    pub fn generate_text_string(&self, grammar: &Grammar, notation: &Notation) -> String {
+        let rows: Vec<[String; 4]> = self
+            .steps
+            .iter()
+            .enumerate()
+            .map(|(index, step)| {
+                [
+                    (index + 1).to_string(),
+                    step.format_stack(grammar, notation),
+                    step.format_input(grammar),
+                    step.action.format(grammar, notation),
+                ]
+            })
+            .collect();
+        let header = [
+            String::from("Step"),
+            String::from("Stack"),
+            String::from("Input"),
+            String::from("Action"),
+        ];
+
+        let mut widths = header.clone().map(|cell| cell.chars().count());
+        for row in rows.iter() {
+            for (width, cell) in widths.iter_mut().zip(row.iter()) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let mut lines = vec![format!("{} parsing of {:?}", self.kind, self.sentence)];
+        for row in std::iter::once(&header).chain(rows.iter()) {
+            // Input is right aligned, as it is consumed from the left
+            lines.push(format!(
+                "{:<w0$}  {:<w1$}  {:>w2$}  {}",
+                row[0],
+                row[1],
+                row[2],
+                row[3],
+                w0 = widths[0],
+                w1 = widths[1],
+                w2 = widths[2]
+            ));
+        }
+        lines.push(format!(
+            "Result: {}",
+            self.outcome.format(grammar, notation)
+        ));
+        lines.join("\n")
+    }
+}
+
+impl Grammar {
+    /// Splits a sentence into terminals: on whitespace if it contains any, one character per
+    /// terminal otherwise. Returns the name of the first symbol which is not a terminal of the
+    /// grammar as error.
+    pub fn tokenize_sentence(&self, sentence: &str) -> Result<Vec<char>, String> {
+        let words: Vec<String> = if sentence.contains(char::is_whitespace) {
+            sentence.split_whitespace().map(String::from).collect()
+        } else {
+            sentence.chars().map(String::from).collect()
+        };
+
+        words
+            .into_iter()
+            .map(|word| {
+                let mut chars = word.chars();
+                let symbol = match (chars.next(), chars.next()) {
+                    (Some(c), None) if !self.symbol_names.contains_key(&c) => Some(c),
+                    _ => self.get_symbol_by_name(&word),
+                };
+                symbol.filter(|s| self.terms.contains(s)).ok_or(word)
+            })
+            .collect()
+    }
+
+    /// Simulates the parsing of a sentence with the table of the given kind, recording the
+    /// stack, the remaining input and the action taken at each step
+    pub fn simulate_parsing(&self, sentence: &str, kind: ParsingTableKind) -> ParseTrace {
+        let mut trace = ParseTrace {
+            kind,
+            sentence: sentence.to_string(),
+            steps: vec![],
+            outcome: ParseOutcome::Accepted,
+            tree: None,
+        };
+
+        let mut input = match self.tokenize_sentence(sentence) {
+            Ok(input) => input,
+            Err(word) => {
+                trace.outcome = ParseOutcome::Rejected(Rejection::UnknownTerminal { word });
+                return trace;
+            }
+        };
+        input.push('$');
+
+        (trace.outcome, trace.tree) = match self.get_parsing_table(kind, None) {
+            ParsingTable::Ll1(table) => self.simulate_ll1(&table, &input, &mut trace.steps),
+            ParsingTable::Lr { rows, .. } => self.simulate_lr(&rows, &input, &mut trace.steps),
+        };
+        trace
+    }
+
+    fn simulate_ll1(
+        &self,
+        table: &HashMap<char, HashMap<char, Vec<usize>>>,
+        input: &[char],
+        steps: &mut Vec<ParseStep>,
+    ) -> (ParseOutcome, Option<ParseTree>) {
+        let Some(start_symbol) = self.get_start_symbol() else {
+            return (ParseOutcome::Rejected(Rejection::NoProductions), None);
+        };
+        let mut stack = vec!['$', start_symbol];
+        let mut position = 0;
+        // The productions expanded so far, which are a leftmost derivation
+        let mut derivation = vec![];
+
+        while steps.len() < MAX_PARSE_STEPS {
+            let top = *stack.last().unwrap();
+            let lookahead = input[position];
+            let mut step = ParseStep {
+                stack: stack.iter().map(|s| StackEntry::Symbol(*s)).collect(),
+                input: input[position..].to_vec(),
+                action: TraceAction::Error,
+            };
+
+            if top == '$' && lookahead == '$' {
+                step.action = TraceAction::Accept;
+                steps.push(step);
+                let tree = ParseTree::from_leftmost_derivation(
+                    self,
+                    start_symbol,
+                    &mut derivation.into_iter(),
+                );
+                return (ParseOutcome::Accepted, Some(tree));
+            }
+
+            if !self.non_terms.contains(&top) {
+                if top != lookahead {
+                    steps.push(step);
+                    let rejection = Rejection::Mismatch {
+                        expected: top,
+                        found: lookahead,
+                    };
+                    return (ParseOutcome::Rejected(rejection), None);
+                }
+                step.action = TraceAction::Match(top);
+                steps.push(step);
+                stack.pop();
+                position += 1;
+                continue;
+            }
+
+            let prod_indices = table
+                .get(&top)
+                .and_then(|row| row.get(&lookahead))
+                .cloned()
+                .unwrap_or_default();
+            match prod_indices.as_slice() {
+                [] => {
+                    steps.push(step);
+                    let rejection = Rejection::NoProduction {
+                        non_term: top,
+                        lookahead,
+                    };
+                    return (ParseOutcome::Rejected(rejection), None);
+                }
+                [prod_index] => {
+                    let production = &self.productions[*prod_index];
+                    step.action = TraceAction::Expand(*prod_index);
+                    steps.push(step);
+                    stack.pop();
+                    stack.extend(production.body.iter().rev());
+                    derivation.push(*prod_index);
+                }
+                _ => {
+                    step.action = TraceAction::Conflict;
+                    steps.push(step);
+                    let cell = ConflictCell::Ll1 {
+                        non_term: top,
+                        term: lookahead,
+                        productions: prod_indices,
+                    };
+                    return (ParseOutcome::Conflict(cell), None);
+                }
+            }
+        }
+
+        let rejection = Rejection::TooManySteps {
+            max_steps: MAX_PARSE_STEPS,
+        };
+        (ParseOutcome::Rejected(rejection), None)
+    }
+
+    fn simulate_lr(
+        &self,
+        rows: &[HashMap<char, Vec<Action>>],
+        input: &[char],
+        steps: &mut Vec<ParseStep>,
+    ) -> (ParseOutcome, Option<ParseTree>) {
+        if rows.is_empty() {
+            return (ParseOutcome::Rejected(Rejection::NoProductions), None);
+        }
+        // States and symbols alternate on the stack, starting from state 0
+        let mut states = vec![0];
+        let mut symbols: Vec<char> = vec![];
+        // The subtrees of the symbols on the stack
+        let mut trees: Vec<ParseTree> = vec![];
+        let mut position = 0;
+
+        while steps.len() < MAX_PARSE_STEPS {
+            let state = *states.last().unwrap();
+            let lookahead = input[position];
+            let mut stack = vec![StackEntry::State(states[0])];
+            for (symbol, state) in symbols.iter().zip(states.iter().skip(1)) {
+                stack.push(StackEntry::Symbol(*symbol));
+                stack.push(StackEntry::State(*state));
+            }
+            let mut step = ParseStep {
+                stack,
+                input: input[position..].to_vec(),
+                action: TraceAction::Error,
+            };
+
+            let actions = rows[state].get(&lookahead).cloned().unwrap_or_default();
+            match actions.as_slice() {
+                [] => {
+                    steps.push(step);
+                    let rejection = Rejection::NoAction { state, lookahead };
+                    return (ParseOutcome::Rejected(rejection), None);
+                }
+                [Action::Shift(to)] => {
+                    step.action = TraceAction::Shift(*to);
+                    steps.push(step);
+                    states.push(*to);
+                    symbols.push(lookahead);
+                    trees.push(ParseTree::leaf(lookahead));
+                    position += 1;
+                }
+                [Action::Reduce(prod_index)] => {
+                    let production = &self.productions[*prod_index];
+                    step.action = TraceAction::Reduce(*prod_index);
+                    steps.push(step);
+
+                    let len = production.body.len();
+                    states.truncate(states.len() - len);
+                    symbols.truncate(symbols.len() - len);
+                    let children = trees.split_off(trees.len() - len);
+                    let from = *states.last().unwrap();
+                    let goto = rows[from].get(&production.driver).and_then(|actions| {
+                        actions.iter().find_map(|action| match action {
+                            Action::Goto(to) => Some(*to),
+                            _ => None,
+                        })
+                    });
+                    match goto {
+                        Some(to) => {
+                            states.push(to);
+                            symbols.push(production.driver);
+                            trees.push(ParseTree {
+                                symbol: production.driver,
+                                children,
+                            });
+                        }
+                        None => {
+                            let rejection = Rejection::NoGoto {
+                                state: from,
+                                non_term: production.driver,
+                            };
+                            return (ParseOutcome::Rejected(rejection), None);
+                        }
+                    }
+                }
+                [Action::Acc] => {
+                    step.action = TraceAction::Accept;
+                    steps.push(step);
+                    return (ParseOutcome::Accepted, trees.pop());
+                }
+                _ => {
+                    step.action = TraceAction::Conflict;
+                    steps.push(step);
+                    let cell = ConflictCell::Lr {
+                        state,
+                        symbol: lookahead,
+                        actions,
+                    };
+                    return (ParseOutcome::Conflict(cell), None);
+                }
+            }
+        }
+
+        let rejection = Rejection::TooManySteps {
+            max_steps: MAX_PARSE_STEPS,
+        };
+        (ParseOutcome::Rejected(rejection), None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grammar::{create_grammar::create_grammar_from_str, notation::NotationProfile};
+
+    #[test]
+    fn formats_the_trace_with_the_notation() {
+        let grammar = create_grammar_from_str("S -> a S b | .").unwrap();
+        let trace = grammar.simulate_parsing("aab", ParsingTableKind::Slr1);
+        let notation = Notation::from_profile(NotationProfile::Dragon);
+
+        let step = &trace.steps[4];
+        assert_eq!(
+            step.format_stack(&grammar, &notation),
+            "I0 a I2 a I2 S I3 b I4"
+        );
+        assert_eq!(step.format_input(&grammar), "$");
+        assert_eq!(step.action.format(&grammar, &notation), "reduce S → aSb");
+        assert_eq!(
+            trace.outcome,
+            ParseOutcome::Rejected(Rejection::NoAction {
+                state: 3,
+                lookahead: '$'
+            })
+        );
+        assert_eq!(
+            trace.outcome.format(&grammar, &notation),
+            "rejected: no action in state I3 on `$`"
+        );
+    }
+}
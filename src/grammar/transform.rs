@@ -0,0 +1,334 @@
+use std::collections::{HashMap, HashSet};
+
+use super::{grammar::Grammar, parse_structs::Production};
+use crate::args::error::TransformError;
+
+/// Bodies of the productions of each non terminal, together with their `%prec` terminal
+type BodiesByDriver = HashMap<char, Vec<(Vec<char>, Option<char>)>>;
+
+impl Grammar {
+    /// Returns an equivalent grammar without left recursion, using the classic algorithm: non
+    /// terminals are taken in the order of the grammar, each `A -> B γ` where `B` comes before
+    /// `A` and leads back to it is replaced by the bodies of `B`, then the immediate left
+    /// recursion of `A` is removed introducing a helper non terminal `A_1`. Non terminals which
+    /// are not left recursive are left untouched, and the `A -> A` bodies (given or produced
+    /// by the substitutions) are dropped, as they add nothing to the language.
+    ///
+    /// Fails when a non terminal is left with no body besides the left recursive ones, or when
+    /// the left recursion is hidden behind a nullable prefix (e.g. `A -> B A a` with `B`
+    /// nullable), which the classic algorithm doesn't remove as it requires a grammar without
+    /// ε-productions.
+    pub fn remove_left_recursion(&self) -> Result<Grammar, TransformError> {
+        let mut grammar = self.clone();
+        let original_order = self.get_sorted_non_terms();
+        let mut order = original_order.clone();
+        let mut bodies = self.get_bodies_by_driver();
+
+        for (i, driver) in original_order.iter().enumerate() {
+            if !Self::left_derives(*driver, *driver, &bodies) {
+                continue;
+            }
+
+            // Only the non terminals leading back to `driver` need to be substituted
+            for previous in original_order[..i].iter() {
+                if !Self::left_derives(*previous, *driver, &bodies) {
+                    continue;
+                }
+                let previous_bodies = bodies[previous].clone();
+                let current_bodies = bodies.remove(driver).unwrap();
+                let new_bodies = current_bodies
+                    .into_iter()
+                    .flat_map(|(body, precedence)| {
+                        if body.first() == Some(previous) {
+                            previous_bodies
+                                .iter()
+                                .map(|(previous_body, _)| {
+                                    ([previous_body.as_slice(), &body[1..]].concat(), precedence)
+                                })
+                                .collect()
+                        } else {
+                            vec![(body, precedence)]
+                        }
+                    })
+                    .collect();
+                bodies.insert(*driver, new_bodies);
+            }
+
+            // `A -> A` bodies are useless and would make the helper loop on itself
+            let (recursive, others): (Vec<_>, Vec<_>) = bodies[driver]
+                .iter()
+                .filter(|(body, _)| *body != [*driver])
+                .cloned()
+                .partition(|(body, _)| body.first() == Some(driver));
+            if others.is_empty() {
+                let non_term = grammar.get_symbol_name(*driver);
+                return Err(if recursive.is_empty() {
+                    TransformError::Cycle { non_term }
+                } else {
+                    TransformError::OnlyLeftRecursive { non_term }
+                });
+            }
+            if recursive.is_empty() {
+                bodies.insert(*driver, others);
+                continue;
+            }
+
+            let helper = grammar.new_helper_non_term(*driver);
+            bodies.insert(
+                *driver,
+                others
+                    .into_iter()
+                    .map(|(body, precedence)| ([body.as_slice(), &[helper]].concat(), precedence))
+                    .collect(),
+            );
+            let mut helper_bodies: Vec<(Vec<char>, Option<char>)> = recursive
+                .into_iter()
+                .map(|(body, precedence)| ([&body[1..], &[helper]].concat(), precedence))
+                .collect();
+            helper_bodies.push((vec![], None));
+            bodies.insert(helper, helper_bodies);
+
+            let position = order.iter().position(|s| s == driver).unwrap();
+            order.insert(position + 1, helper);
+        }
+
+        grammar.set_productions(Self::get_productions_from_bodies(&order, &bodies));
+        match grammar.find_left_recursive() {
+            Some(non_term) => Err(TransformError::HiddenLeftRecursion {
+                non_term: grammar.get_symbol_name(non_term),
+            }),
+            None => Ok(grammar),
+        }
+    }
+
+    /// Returns the first non terminal (in the order of the grammar) deriving a sentential form
+    /// starting with itself, looking past the nullable non terminals at the start of the bodies
+    fn find_left_recursive(&self) -> Option<char> {
+        let first_follow_set = self.get_first_follow_table();
+        let is_nullable =
+            |symbol: &char| first_follow_set.get(symbol).is_some_and(|set| set.nullable);
+        let mut left_symbols: HashMap<char, Vec<char>> = HashMap::new();
+        for production in self.productions.iter() {
+            let symbols = left_symbols.entry(production.driver).or_default();
+            for symbol in production.body.iter() {
+                if self.non_terms.contains(symbol) {
+                    symbols.push(*symbol);
+                }
+                if !is_nullable(symbol) {
+                    break;
+                }
+            }
+        }
+
+        self.get_sorted_non_terms().into_iter().find(|non_term| {
+            let mut visited = HashSet::new();
+            let mut stack = vec![*non_term];
+            while let Some(symbol) = stack.pop() {
+                for next in left_symbols.get(&symbol).into_iter().flatten() {
+                    if next == non_term {
+                        return true;
+                    }
+                    if visited.insert(*next) {
+                        stack.push(*next);
+                    }
+                }
+            }
+            false
+        })
+    }
+
+    /// Returns an equivalent grammar where no two bodies of the same non terminal start with
+    /// the same symbol: the bodies sharing the longest common prefix `α` are replaced by
+    /// `A -> α A_1`, the helper `A_1` deriving their remaining suffixes. The helpers are factored
+    /// in turn, until no common prefix is left.
+    pub fn left_factor(&self) -> Grammar {
+        let mut grammar = self.clone();
+        let mut order = self.get_sorted_non_terms();
+        let mut bodies = self.get_bodies_by_driver();
+        // Number of helpers already created for each non terminal, placed right after it
+        let mut helper_counts: HashMap<char, usize> = HashMap::new();
+
+        let mut i = 0;
+        while i < order.len() {
+            let driver = order[i];
+            let current_bodies = bodies[&driver].clone();
+            let shared_symbol = current_bodies
+                .iter()
+                .enumerate()
+                .find_map(|(index, (body, _))| {
+                    let first = body.first()?;
+                    current_bodies[index + 1..]
+                        .iter()
+                        .any(|(other, _)| other.first() == Some(first))
+                        .then_some(*first)
+                });
+            let Some(shared_symbol) = shared_symbol else {
+                i += 1;
+                continue;
+            };
+
+            let group: Vec<&Vec<char>> = current_bodies
+                .iter()
+                .map(|(body, _)| body)
+                .filter(|body| body.first() == Some(&shared_symbol))
+                .collect();
+            let prefix_len = (1..)
+                .take_while(|len| {
+                    group
+                        .iter()
+                        .all(|body| body.len() >= *len && body[..*len] == group[0][..*len])
+                })
+                .last()
+                .unwrap_or(1);
+            let prefix = group[0][..prefix_len].to_vec();
+
+            let helper = grammar.new_helper_non_term(driver);
+            let mut new_bodies = vec![];
+            let mut helper_bodies = vec![];
+            for (body, precedence) in current_bodies {
+                if body.first() != Some(&shared_symbol) {
+                    new_bodies.push((body, precedence));
+                    continue;
+                }
+                // The factored body takes the place of the first body of the group
+                if helper_bodies.is_empty() {
+                    new_bodies.push(([prefix.as_slice(), &[helper]].concat(), None));
+                }
+                helper_bodies.push((body[prefix_len..].to_vec(), precedence));
+            }
+            bodies.insert(driver, new_bodies);
+            bodies.insert(helper, helper_bodies);
+
+            let helper_count = helper_counts.entry(driver).or_default();
+            order.insert(i + 1 + *helper_count, helper);
+            *helper_count += 1;
+        }
+
+        grammar.set_productions(Self::get_productions_from_bodies(&order, &bodies));
+        grammar
+    }
+
+    fn get_bodies_by_driver(&self) -> BodiesByDriver {
+        let mut bodies: BodiesByDriver = HashMap::new();
+        for production in self.productions.iter() {
+            bodies
+                .entry(production.driver)
+                .or_default()
+                .push((production.body.clone(), production.precedence));
+        }
+        bodies
+    }
+
+    fn get_productions_from_bodies(order: &[char], bodies: &BodiesByDriver) -> Vec<Production> {
+        order
+            .iter()
+            .flat_map(|driver| {
+                bodies[driver].iter().map(|(body, precedence)| Production {
+                    index: None,
+                    driver: *driver,
+                    body: body.clone(),
+                    precedence: *precedence,
+                })
+            })
+            .collect()
+    }
+
+    /// Whether `from` can derive, in at least one step, a sentential form starting with `to`,
+    /// looking only at the first symbol of each body
+    fn left_derives(from: char, to: char, bodies: &BodiesByDriver) -> bool {
+        let mut visited = HashSet::new();
+        let mut stack = vec![from];
+        while let Some(symbol) = stack.pop() {
+            for (body, _) in bodies.get(&symbol).into_iter().flatten() {
+                match body.first() {
+                    Some(first) if *first == to => return true,
+                    Some(first) if bodies.contains_key(first) && visited.insert(*first) => {
+                        stack.push(*first)
+                    }
+                    _ => {}
+                }
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grammar::create_grammar::create_grammar_from_str;
+
+    fn remove_left_recursion(definition: &str) -> Result<String, TransformError> {
+        let grammar = create_grammar_from_str(definition).unwrap();
+        grammar
+            .remove_left_recursion()
+            .map(|grammar| grammar.get_definition_string())
+    }
+
+    #[test]
+    fn removes_immediate_left_recursion() {
+        assert_eq!(
+            remove_left_recursion("E -> E + T | T . T -> a .").unwrap(),
+            "E -> T E_1 .\nE_1 -> '+' T E_1 | ε .\nT -> a .\n"
+        );
+    }
+
+    #[test]
+    fn drops_self_productions_left_by_the_substitution() {
+        let result = remove_left_recursion("S -> A | a . A -> S | b .").unwrap();
+        assert_eq!(result, "S -> A | a .\nA -> a | b .\n");
+    }
+
+    #[test]
+    fn drops_self_productions() {
+        assert_eq!(remove_left_recursion("S -> S | a .").unwrap(), "S -> a .\n");
+    }
+
+    /// Builds the grammar bypassing `create_grammar_from_str`, which rejects the non productive
+    /// non terminals these errors are about
+    fn remove_left_recursion_of(productions: &[(char, &str)]) -> Result<Grammar, TransformError> {
+        let mut grammar = Grammar::new();
+        grammar.set_productions(
+            productions
+                .iter()
+                .map(|(driver, body)| Production {
+                    index: None,
+                    driver: *driver,
+                    body: body.chars().collect(),
+                    precedence: None,
+                })
+                .collect(),
+        );
+        grammar.remove_left_recursion()
+    }
+
+    #[test]
+    fn fails_on_cycles() {
+        assert_eq!(
+            remove_left_recursion_of(&[('A', "B"), ('B', "A"), ('B', "A")]).err(),
+            Some(TransformError::Cycle {
+                non_term: "B".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn fails_on_only_left_recursive_bodies() {
+        assert_eq!(
+            remove_left_recursion_of(&[('S', "Sa")]).err(),
+            Some(TransformError::OnlyLeftRecursive {
+                non_term: "S".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn fails_on_left_recursion_behind_nullable_prefix() {
+        assert_eq!(
+            remove_left_recursion("S -> B S a | b . B -> c | ."),
+            Err(TransformError::HiddenLeftRecursion {
+                non_term: "S".to_string()
+            })
+        );
+    }
+}
@@ -0,0 +1,381 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use serde::Serialize;
+
+use crate::grammar::{grammar::Grammar, notation::Notation};
+use crate::lr0::{AutomatonEdge, Lr0Item, Lr0ItemOwned, get_parsing_automaton};
+
+/// A transition of the item NFA, `symbol` being `None` for the ε-transitions
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NfaEdge {
+    pub from: usize,
+    pub symbol: Option<char>,
+    pub to: usize,
+}
+
+/// The non deterministic automaton of the LR(0) items: one state per item, a transition by the
+/// next symbol of every item to the item with the dot moved past it and an ε-transition from
+/// every item whose next symbol is a non terminal to the initial items of its productions
+pub struct ItemNfa<'a> {
+    pub grammar: &'a Grammar,
+    /// The items of the augmented production first, then the ones of every production by dot
+    /// position. The initial state is the first one.
+    pub items: Vec<Lr0Item<'a>>,
+    pub edges: Vec<NfaEdge>,
+}
+
+/// A step of the subset construction: the items reached from a state of the DFA by a symbol,
+/// their ε-closure and the state of the DFA it is. The first step, without source state and
+/// symbol, is the ε-closure of the initial item.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SubsetStep {
+    pub from: Option<usize>,
+    pub symbol: Option<char>,
+    /// States of the NFA reached by the symbol, the kernel of the target state
+    pub moved: Vec<usize>,
+    /// The ε-closure of `moved`
+    pub closure: Vec<usize>,
+    pub to: usize,
+    /// Whether the target state has been found by this step
+    pub is_new: bool,
+}
+
+/// The DFA obtained from an `ItemNfa` by the subset construction, with the log of its steps
+pub struct SubsetConstruction<'a> {
+    pub nfa: ItemNfa<'a>,
+    /// States of the NFA making up every state of the DFA, the kernel first
+    pub states: Vec<Vec<usize>>,
+    pub edges: Vec<AutomatonEdge>,
+    pub steps: Vec<SubsetStep>,
+    /// State of `get_parsing_automaton` holding the same items as every state of the DFA
+    pub lr0_states: Vec<Option<usize>>,
+}
+
+impl<'a> ItemNfa<'a> {
+    pub fn new(grammar: &'a Grammar) -> ItemNfa<'a> {
+        let mut items = vec![];
+        for production in grammar
+            .starting_prod
+            .iter()
+            .chain(grammar.productions.iter())
+        {
+            let mut item = production.as_lr0_item();
+            items.push(item.clone());
+            while !item.is_complete() {
+                item.advance();
+                items.push(item.clone());
+            }
+        }
+        let positions: HashMap<&Lr0Item, usize> = items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| (item, index))
+            .collect();
+
+        let mut edges = vec![];
+        for (index, item) in items.iter().enumerate() {
+            let Some(next_symbol) = item.next_symbol() else {
+                continue;
+            };
+            edges.push(NfaEdge {
+                from: index,
+                symbol: Some(next_symbol),
+                to: positions[&item.next_item().unwrap()],
+            });
+            for production in grammar
+                .productions
+                .iter()
+                .filter(|production| production.driver == next_symbol)
+            {
+                edges.push(NfaEdge {
+                    from: index,
+                    symbol: None,
+                    to: positions[&production.as_lr0_item()],
+                });
+            }
+        }
+
+        ItemNfa {
+            grammar,
+            items,
+            edges,
+        }
+    }
+
+    /// The given states followed by the ones reachable from them by ε-transitions, in the
+    /// order they are reached
+    pub fn epsilon_closure(&self, states: &[usize]) -> Vec<usize> {
+        let mut rv = states.to_vec();
+        let mut queue: VecDeque<usize> = states.iter().copied().collect();
+        while let Some(state) = queue.pop_front() {
+            for edge in self.edges.iter() {
+                if edge.from == state && edge.symbol.is_none() && !rv.contains(&edge.to) {
+                    rv.push(edge.to);
+                    queue.push_back(edge.to);
+                }
+            }
+        }
+        rv
+    }
+
+    /// States reached from the given ones by a transition by the symbol
+    pub fn move_by(&self, states: &[usize], symbol: char) -> Vec<usize> {
+        states
+            .iter()
+            .flat_map(|state| {
+                self.edges
+                    .iter()
+                    .filter(move |edge| edge.from == *state && edge.symbol == Some(symbol))
+                    .map(|edge| edge.to)
+            })
+            .collect()
+    }
+
+    /// Turns the NFA into a DFA by the subset construction. The symbols leaving every state are
+    /// tried in the order `get_parsing_automaton` follows (the next symbols of the kernel first,
+    /// then the other ones in the order of the columns of the parsing table), so that the states
+    /// are found in the same order.
+    pub fn subset_construction(self) -> SubsetConstruction<'a> {
+        let grammar = self.grammar;
+        let mut states: Vec<Vec<usize>> = vec![];
+        let mut edges = vec![];
+        let mut steps = vec![];
+        if !self.items.is_empty() {
+            let closure = self.epsilon_closure(&[0]);
+            steps.push(SubsetStep {
+                from: None,
+                symbol: None,
+                moved: vec![0],
+                closure: closure.clone(),
+                to: 0,
+                is_new: true,
+            });
+            states.push(closure);
+        }
+
+        // The kernel of every state is the set of NFA states it was reached by
+        let mut kernel_sizes = vec![1];
+        let sorted_symbols = [grammar.get_sorted_terms(), grammar.get_sorted_non_terms()].concat();
+        let mut current = 0;
+        while current < states.len() {
+            let state = states[current].clone();
+            let next_symbols = |items: &[usize]| -> HashSet<char> {
+                items
+                    .iter()
+                    .filter_map(|item| self.items[*item].next_symbol())
+                    .collect()
+            };
+            let kernel_symbols = next_symbols(&state[..kernel_sizes[current]]);
+            let mut symbols: Vec<char> = vec![];
+            for item in state[..kernel_sizes[current]].iter() {
+                if let Some(symbol) = self.items[*item].next_symbol()
+                    && !symbols.contains(&symbol)
+                {
+                    symbols.push(symbol);
+                }
+            }
+            let closure_symbols = next_symbols(&state[kernel_sizes[current]..]);
+            symbols.extend(sorted_symbols.iter().filter(|symbol| {
+                closure_symbols.contains(symbol) && !kernel_symbols.contains(symbol)
+            }));
+
+            for symbol in symbols {
+                let moved = self.move_by(&state, symbol);
+                let closure = self.epsilon_closure(&moved);
+                let as_set: HashSet<&usize> = closure.iter().collect();
+                let existing = states
+                    .iter()
+                    .position(|other| other.iter().collect::<HashSet<&usize>>() == as_set);
+                let to = existing.unwrap_or(states.len());
+                if existing.is_none() {
+                    kernel_sizes.push(moved.len());
+                    states.push(closure.clone());
+                }
+                edges.push(AutomatonEdge {
+                    from: current,
+                    symbol,
+                    to,
+                });
+                steps.push(SubsetStep {
+                    from: Some(current),
+                    symbol: Some(symbol),
+                    moved,
+                    closure,
+                    to,
+                    is_new: existing.is_none(),
+                });
+            }
+            current += 1;
+        }
+
+        let automaton = get_parsing_automaton(grammar);
+        let lr0_sets: Vec<HashSet<Lr0ItemOwned>> = automaton
+            .nodes
+            .iter()
+            .map(|node| {
+                node.kernel
+                    .iter()
+                    .chain(node.closure.iter())
+                    .map(Lr0ItemOwned::from)
+                    .collect()
+            })
+            .collect();
+        let lr0_states = states
+            .iter()
+            .map(|state| {
+                let items: HashSet<Lr0ItemOwned> = state
+                    .iter()
+                    .map(|item| Lr0ItemOwned::from(&self.items[*item]))
+                    .collect();
+                lr0_sets.iter().position(|set| *set == items)
+            })
+            .collect();
+
+        SubsetConstruction {
+            nfa: self,
+            states,
+            edges,
+            steps,
+            lr0_states,
+        }
+    }
+
+    /// Returns the NFA in DOT notation, with a state per item and the ε-transitions dashed
+    pub fn generate_dot_notation_string(&self, notation: &Notation) -> String {
+        let mut rv = String::from("digraph G {\nrankdir=LR\nnode[shape=box]\n\n");
+        for (index, item) in self.items.iter().enumerate() {
+            let label = item
+                .format(self.grammar, notation)
+                .replace("->", "→")
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"");
+            // The initial state is the only one drawn with a double border
+            let peripheries = if index == 0 { ", peripheries=2" } else { "" };
+            rv.push_str(&format!("{} [label=\"{}\"{}]\n", index, label, peripheries));
+        }
+        rv.push_str("\n\n//nodes\n");
+        for edge in self.edges.iter() {
+            match edge.symbol {
+                Some(symbol) => rv.push_str(&format!(
+                    "{} -> {} [label=\"{}\"]\n",
+                    edge.from,
+                    edge.to,
+                    self.grammar
+                        .get_symbol_name(symbol)
+                        .replace('\\', "\\\\")
+                        .replace('"', "\\\"")
+                )),
+                None => rv.push_str(&format!(
+                    "{} -> {} [label=\"{}\", style=dashed]\n",
+                    edge.from, edge.to, notation.epsilon
+                )),
+            }
+        }
+        rv.push_str("}\n");
+        rv
+    }
+}
+
+impl SubsetConstruction<'_> {
+    /// Whether the DFA has the same states as `get_parsing_automaton`, in the same order
+    pub fn matches_lr0_automaton(&self) -> bool {
+        self.states.len() == get_parsing_automaton(self.nfa.grammar).nodes.len()
+            && self
+                .lr0_states
+                .iter()
+                .enumerate()
+                .all(|(index, lr0_state)| *lr0_state == Some(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grammar::create_grammar::create_grammar_from_str;
+    use crate::lr0::get_sorted_edges;
+
+    /// Checks the construction against `get_parsing_automaton` without going through
+    /// `lr0_states`: same item sets and same edges, state by state
+    fn assert_same_as_lr0_automaton(construction: &SubsetConstruction) {
+        let automaton = get_parsing_automaton(construction.nfa.grammar);
+        assert_eq!(construction.states.len(), automaton.nodes.len());
+        for (state, node) in construction.states.iter().zip(automaton.nodes.iter()) {
+            let items: HashSet<Lr0ItemOwned> = state
+                .iter()
+                .map(|item| Lr0ItemOwned::from(&construction.nfa.items[*item]))
+                .collect();
+            let lr0_items: HashSet<Lr0ItemOwned> = node
+                .kernel
+                .iter()
+                .chain(node.closure.iter())
+                .map(Lr0ItemOwned::from)
+                .collect();
+            assert_eq!(items, lr0_items);
+        }
+
+        let sort_key = |edge: &AutomatonEdge| (edge.from, edge.symbol, edge.to);
+        let mut edges = construction.edges.clone();
+        edges.sort_by_key(sort_key);
+        let mut lr0_edges = get_sorted_edges(&automaton.edges);
+        lr0_edges.sort_by_key(sort_key);
+        assert_eq!(edges, lr0_edges);
+    }
+
+    #[test]
+    fn epsilon_productions_give_the_lr0_automaton() {
+        let grammar = create_grammar_from_str("S -> A B . A -> a A | ε . B -> b | ε .").unwrap();
+        let construction = ItemNfa::new(&grammar).subset_construction();
+
+        assert!(construction.matches_lr0_automaton());
+        assert_eq!(
+            construction.lr0_states,
+            (0..construction.states.len()).map(Some).collect::<Vec<_>>()
+        );
+        assert_same_as_lr0_automaton(&construction);
+        // The complete items of the empty bodies are reached by ε-transitions only
+        let empty_items: Vec<usize> = construction
+            .nfa
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| item.production.body.is_empty())
+            .map(|(index, _)| index)
+            .collect();
+        assert_eq!(empty_items.len(), 2);
+        for index in empty_items {
+            assert!(
+                construction
+                    .nfa
+                    .edges
+                    .iter()
+                    .filter(|edge| edge.to == index)
+                    .all(|edge| edge.symbol.is_none())
+            );
+        }
+    }
+
+    #[test]
+    fn shared_kernels_are_found_once() {
+        let grammar = create_grammar_from_str("S -> a A | b A . A -> c .").unwrap();
+        let construction = ItemNfa::new(&grammar).subset_construction();
+
+        assert!(construction.matches_lr0_automaton());
+        assert_eq!(
+            construction.lr0_states,
+            (0..construction.states.len()).map(Some).collect::<Vec<_>>()
+        );
+        assert_same_as_lr0_automaton(&construction);
+        // `A -> c•` is reached by `c` both after `a` and after `b`, the second time as a
+        // state of the DFA already found
+        let by_c: Vec<&SubsetStep> = construction
+            .steps
+            .iter()
+            .filter(|step| step.symbol == Some('c'))
+            .collect();
+        assert_eq!(by_c.len(), 2);
+        assert_ne!(by_c[0].from, by_c[1].from);
+        assert_eq!(by_c[0].to, by_c[1].to);
+        assert!(by_c[0].is_new);
+        assert!(!by_c[1].is_new);
+    }
+}
@@ -0,0 +1,10 @@
+//! Tools to analyse context free grammars: FIRST and FOLLOW sets, LR(0) and LR(1) automata,
+//! LL(1) and LR parsing tables, classification, transformations and parse simulation, rendered
+//! by the backends of `output`.
+pub mod args;
+pub mod grammar;
+pub mod item_nfa;
+pub mod lr0;
+pub mod lr1;
+pub mod output;
+pub mod util;
@@ -1,6 +1,16 @@
 use std::collections::{HashMap, HashSet, VecDeque};
 
-use crate::grammar::{grammar::Grammar, parse_structs::Production};
+use serde::{Deserialize, Serialize};
+
+use crate::grammar::{
+    grammar::Grammar,
+    notation::Notation,
+    parse_structs::{Action, Production},
+};
+use crate::output::{
+    AutomatonView,
+    dot::{self, DotStyle},
+};
 
 #[derive(Clone, PartialEq, Eq, Hash)]
 pub struct Lr0Item<'a> {
@@ -8,9 +18,43 @@ pub struct Lr0Item<'a> {
     dot_position: usize,
 }
 
+/// An LR(0) item which doesn't borrow the grammar, identified by the index of its production
+/// (`None` for the augmented starting production) and the dot position. Like the other owned
+/// types it can be stored, sent across threads and deserialized from the JSON output.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Lr0ItemOwned {
-    pub production: Production,
-    dot_position: usize,
+    pub production: Option<usize>,
+    pub dot_position: usize,
+}
+
+impl From<&Lr0Item<'_>> for Lr0ItemOwned {
+    fn from(item: &Lr0Item<'_>) -> Self {
+        Lr0ItemOwned {
+            production: item.production.index,
+            dot_position: item.dot_position,
+        }
+    }
+}
+
+impl Lr0ItemOwned {
+    /// The production of the item in the given grammar, `None` if the grammar has no such
+    /// production
+    pub fn production<'a>(&self, grammar: &'a Grammar) -> Option<&'a Production> {
+        match self.production {
+            Some(index) => grammar.productions.get(index),
+            None => grammar.starting_prod.as_ref(),
+        }
+    }
+
+    /// The item borrowing the production from the given grammar, `None` if the grammar has no
+    /// such production or its body is shorter than the dot position
+    pub fn to_item<'a>(&self, grammar: &'a Grammar) -> Option<Lr0Item<'a>> {
+        let production = self.production(grammar)?;
+        (self.dot_position <= production.body.len()).then_some(Lr0Item {
+            production,
+            dot_position: self.dot_position,
+        })
+    }
 }
 
 impl<'a> Lr0Item<'a> {
@@ -21,6 +65,10 @@ impl<'a> Lr0Item<'a> {
         }
     }
 
+    pub fn dot_position(&self) -> usize {
+        self.dot_position
+    }
+
     pub fn is_complete(&self) -> bool {
         self.dot_position >= self.production.body.len()
     }
@@ -40,6 +88,11 @@ impl<'a> Lr0Item<'a> {
         }
     }
 
+    /// Formats the item using the symbol names of the grammar it belongs to
+    pub fn format(&self, grammar: &Grammar, notation: &Notation) -> String {
+        notation.item(grammar, self.production, self.dot_position)
+    }
+
     pub fn advance(&mut self) {
         if !self.is_complete() {
             self.dot_position += 1;
@@ -49,67 +102,51 @@ impl<'a> Lr0Item<'a> {
 
 impl std::fmt::Display for Lr0Item<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let body: String = self.production.body.iter().collect();
-        let before_dot = &body[..self.dot_position];
-        let after_dot = &body[self.dot_position..];
+        let (before_dot, after_dot) = self.production.body.split_at(self.dot_position);
         write!(
             f,
             "{} -> {}•{}",
             self.production.driver,
-            before_dot,
-            if after_dot.ends_with(" ") {
-                ""
-            } else {
-                after_dot
-            }
+            before_dot.iter().collect::<String>(),
+            after_dot.iter().collect::<String>()
         )
     }
 }
 
 pub struct Lr0Automaton<'a> {
+    pub grammar: &'a Grammar,
     pub nodes: Vec<Lr0AutomatonNode<'a>>,
     pub edges: HashMap<usize, Vec<(usize, char)>>,
 }
 
 impl Lr0Automaton<'_> {
-    pub fn generate_dot_notation_string(&self) -> String {
-        let mut rv = String::new();
-        rv.push_str("digraph G {\nnode[shape=record]\n\n");
-        for (index, item) in self.nodes.iter().enumerate() {
-            let mut label_kernel = String::new();
-            let mut label_closure = String::new();
-            for prod in &item.kernel {
-                label_kernel.push_str(&format!("{}\\n", prod).replace("->", "→"));
-            }
-            for prod in &item.closure {
-                label_closure.push_str(&format!("{}\\n", prod).replace("->", "→"));
-            }
-            label_kernel = label_kernel.trim_end().to_string();
-            label_closure = label_closure.trim_end().to_string();
-
-            if label_closure.is_empty() {
-                rv.push_str(&format!(
-                    "{} [label=\"{{ {} | {} }}\"]\n",
-                    index, index, label_kernel
-                ));
-            } else {
-                rv.push_str(&format!(
-                    "{} [label=\"{{ {} | {} | {} }}\"]\n",
-                    index, index, label_kernel, label_closure
-                ));
-            }
-        }
-        rv.push_str("\n\n//nodes\n");
-        for (from, neighbours) in &self.edges {
-            for neighbour in neighbours {
-                let to = neighbour.0;
-                let by_char = neighbour.1;
-                rv.push_str(&format!("{} -> {} [label=\"{}\"]\n", from, to, by_char));
-            }
-        }
-        rv.push_str("}\n");
+    /// Items of every state taking part in a conflict of the given parsing table, whose rows
+    /// are indexed by the states of the automaton
+    pub fn get_conflicting_items(
+        &self,
+        parsing_table: &[HashMap<char, Vec<Action>>],
+    ) -> Vec<HashSet<Lr0ItemOwned>> {
+        self.nodes
+            .iter()
+            .zip(parsing_table.iter())
+            .map(|(node, row)| {
+                node.kernel
+                    .iter()
+                    .chain(node.closure.iter())
+                    .filter(|item| {
+                        self.grammar
+                            .is_conflicting_item(row, item.production, item.dot_position)
+                    })
+                    .map(Lr0ItemOwned::from)
+                    .collect()
+            })
+            .collect()
+    }
 
-        rv
+    /// Returns the automaton in DOT notation with the given style, drawing the states with
+    /// conflicts with a red border
+    pub fn generate_dot_notation_string(&self, notation: &Notation, style: &DotStyle) -> String {
+        dot::render_automaton(&AutomatonView::from_lr0(self, notation), style)
     }
 }
 
@@ -136,6 +173,113 @@ impl<'a> std::fmt::Display for Lr0Automaton<'a> {
     }
 }
 
+/// A transition of an automaton, from the state with index `from` to the one with index `to`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AutomatonEdge {
+    pub from: usize,
+    pub symbol: char,
+    pub to: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lr0AutomatonNodeOwned {
+    pub kernel: Vec<Lr0ItemOwned>,
+    pub closure: Vec<Lr0ItemOwned>,
+}
+
+impl From<&Lr0AutomatonNode<'_>> for Lr0AutomatonNodeOwned {
+    fn from(node: &Lr0AutomatonNode<'_>) -> Self {
+        Lr0AutomatonNodeOwned {
+            kernel: node.kernel.iter().map(Lr0ItemOwned::from).collect(),
+            closure: node.closure.iter().map(Lr0ItemOwned::from).collect(),
+        }
+    }
+}
+
+impl Lr0AutomatonNodeOwned {
+    /// The state borrowing the productions from the given grammar, `None` if any of its items
+    /// doesn't belong to it
+    pub fn to_node<'a>(&self, grammar: &'a Grammar) -> Option<Lr0AutomatonNode<'a>> {
+        let to_items = |items: &[Lr0ItemOwned]| -> Option<Vec<Lr0Item<'a>>> {
+            items.iter().map(|item| item.to_item(grammar)).collect()
+        };
+        Some(Lr0AutomatonNode {
+            kernel: to_items(&self.kernel)?,
+            closure: to_items(&self.closure)?,
+        })
+    }
+}
+
+/// An LR(0) automaton which doesn't borrow the grammar, with the edges sorted by source and
+/// target state, so that it can be cached apart from the grammar it was built from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lr0AutomatonOwned {
+    pub nodes: Vec<Lr0AutomatonNodeOwned>,
+    pub edges: Vec<AutomatonEdge>,
+}
+
+impl From<&Lr0Automaton<'_>> for Lr0AutomatonOwned {
+    fn from(automaton: &Lr0Automaton<'_>) -> Self {
+        Lr0AutomatonOwned {
+            nodes: automaton
+                .nodes
+                .iter()
+                .map(Lr0AutomatonNodeOwned::from)
+                .collect(),
+            edges: get_sorted_edges(&automaton.edges),
+        }
+    }
+}
+
+impl From<Lr0Automaton<'_>> for Lr0AutomatonOwned {
+    fn from(automaton: Lr0Automaton<'_>) -> Self {
+        Lr0AutomatonOwned::from(&automaton)
+    }
+}
+
+impl Lr0AutomatonOwned {
+    /// The automaton borrowing the productions from the given grammar, which must be the one
+    /// it was built from: `None` if any of its items doesn't belong to it
+    pub fn to_automaton<'a>(&self, grammar: &'a Grammar) -> Option<Lr0Automaton<'a>> {
+        Some(Lr0Automaton {
+            grammar,
+            nodes: self
+                .nodes
+                .iter()
+                .map(|node| node.to_node(grammar))
+                .collect::<Option<Vec<Lr0AutomatonNode>>>()?,
+            edges: get_edge_map(&self.edges),
+        })
+    }
+}
+
+/// Flattens the edges of an automaton, sorting them by source and target state
+pub fn get_sorted_edges(edges: &HashMap<usize, Vec<(usize, char)>>) -> Vec<AutomatonEdge> {
+    let mut rv: Vec<AutomatonEdge> = edges
+        .iter()
+        .flat_map(|(from, neighbours)| {
+            neighbours.iter().map(|(to, symbol)| AutomatonEdge {
+                from: *from,
+                symbol: *symbol,
+                to: *to,
+            })
+        })
+        .collect();
+    rv.sort_by_key(|edge| (edge.from, edge.to));
+    rv
+}
+
+/// Groups the edges of an automaton by source state, the inverse of `get_sorted_edges`
+pub fn get_edge_map(edges: &[AutomatonEdge]) -> HashMap<usize, Vec<(usize, char)>> {
+    let mut rv: HashMap<usize, Vec<(usize, char)>> = HashMap::new();
+    for edge in edges.iter() {
+        rv.entry(edge.from)
+            .or_default()
+            .push((edge.to, edge.symbol));
+    }
+    rv
+}
+
 #[derive(Clone, PartialEq, Eq, Hash)]
 pub struct Lr0AutomatonNode<'a> {
     pub kernel: Vec<Lr0Item<'a>>,
@@ -147,20 +291,20 @@ impl<'a> Lr0AutomatonNode<'a> {
         let mut rv = vec![];
 
         for item in &self.kernel {
-            if let Some(next_symbol) = item.next_symbol() {
-                if next_symbol == by_char {
-                    let next_item = item.next_item().unwrap();
-                    rv.push(next_item);
-                }
+            if let Some(next_symbol) = item.next_symbol()
+                && next_symbol == by_char
+            {
+                let next_item = item.next_item().unwrap();
+                rv.push(next_item);
             }
         }
 
         for item in &self.closure {
-            if let Some(next_symbol) = item.next_symbol() {
-                if next_symbol == by_char {
-                    let next_item = item.next_item().unwrap();
-                    rv.push(next_item);
-                }
+            if let Some(next_symbol) = item.next_symbol()
+                && next_symbol == by_char
+            {
+                let next_item = item.next_item().unwrap();
+                rv.push(next_item);
             }
         }
 
@@ -168,15 +312,24 @@ impl<'a> Lr0AutomatonNode<'a> {
     }
 }
 
-/// Returns the lr0 parsing automaton for the given grammar.
+/// Returns the lr0 parsing automaton for the given grammar. The automaton of a grammar without
+/// productions has no states.
 pub fn get_parsing_automaton<'a>(grammar: &'a Grammar) -> Lr0Automaton<'a> {
-    let starting_lr0_item: Lr0Item<'a> = grammar.starting_prod.as_ref().unwrap().as_lr0_item();
+    let Some(starting_prod) = grammar.starting_prod.as_ref() else {
+        return Lr0Automaton {
+            grammar,
+            nodes: vec![],
+            edges: HashMap::new(),
+        };
+    };
+    let starting_lr0_item: Lr0Item<'a> = starting_prod.as_lr0_item();
     let first_node = Lr0AutomatonNode {
         kernel: vec![starting_lr0_item.clone()],
         closure: grammar.lr0_closure(vec![starting_lr0_item.clone()]),
     };
 
     let mut automaton: Lr0Automaton<'a> = Lr0Automaton {
+        grammar,
         nodes: vec![first_node.clone()],
         edges: HashMap::new(),
     };
@@ -197,25 +350,24 @@ pub fn get_parsing_automaton<'a>(grammar: &'a Grammar) -> Lr0Automaton<'a> {
         // Kernels characters are processed in the order they appear inside the kernel
         let curr_node = &automaton.nodes[curr_node_index];
         for item in curr_node.kernel.iter() {
-            if let Some(next_symbol) = item.next_symbol() {
-                if !outgoing_chars_set.contains(&next_symbol) {
-                    outgoing_chars.push(next_symbol);
-                    outgoing_chars_set.insert(next_symbol);
-                }
+            if let Some(next_symbol) = item.next_symbol()
+                && !outgoing_chars_set.contains(&next_symbol)
+            {
+                outgoing_chars.push(next_symbol);
+                outgoing_chars_set.insert(next_symbol);
             }
         }
 
         // Closure characters are processed in the order of parsing table columns
-        let sorted_symbols =
-            vec![grammar.get_sorted_terms(), grammar.get_sorted_non_terms()].concat();
+        let sorted_symbols = [grammar.get_sorted_terms(), grammar.get_sorted_non_terms()].concat();
         let mut outgoing_chars_set_from_closure: HashSet<char> = HashSet::new();
 
         for item in curr_node.closure.iter() {
-            if let Some(next_symbol) = item.next_symbol() {
-                if !outgoing_chars_set.contains(&next_symbol) {
-                    outgoing_chars_set.insert(next_symbol);
-                    outgoing_chars_set_from_closure.insert(next_symbol);
-                }
+            if let Some(next_symbol) = item.next_symbol()
+                && !outgoing_chars_set.contains(&next_symbol)
+            {
+                outgoing_chars_set.insert(next_symbol);
+                outgoing_chars_set_from_closure.insert(next_symbol);
             }
         }
         for char in sorted_symbols {
@@ -256,10 +408,7 @@ pub fn get_parsing_automaton<'a>(grammar: &'a Grammar) -> Lr0Automaton<'a> {
             let to = edge.0.1;
             let by_char = edge.1;
 
-            if !automaton.edges.contains_key(&from) {
-                automaton.edges.insert(from, vec![]);
-            }
-            automaton.edges.get_mut(&from).unwrap().push((to, by_char));
+            automaton.edges.entry(from).or_default().push((to, by_char));
         }
     }
 
@@ -278,3 +427,51 @@ pub fn print_closures(grammar: &Grammar) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grammar::create_grammar::create_grammar_from_str;
+
+    #[test]
+    fn owned_automaton_round_trips_through_serde() {
+        let grammar = create_grammar_from_str("E -> E + T | T . T -> ( E ) | a .").unwrap();
+        let automaton = get_parsing_automaton(&grammar);
+
+        let json = serde_json::to_string(&Lr0AutomatonOwned::from(&automaton)).unwrap();
+        let owned: Lr0AutomatonOwned = serde_json::from_str(&json).unwrap();
+        let restored = owned.to_automaton(&grammar).unwrap();
+
+        assert!(restored.nodes == automaton.nodes);
+        assert_eq!(
+            get_sorted_edges(&restored.edges),
+            get_sorted_edges(&automaton.edges)
+        );
+    }
+
+    #[test]
+    fn owned_automaton_does_not_belong_to_other_grammars() {
+        let grammar = create_grammar_from_str("E -> E + T | T . T -> ( E ) | a .").unwrap();
+        let other = create_grammar_from_str("S -> a .").unwrap();
+        let owned = Lr0AutomatonOwned::from(get_parsing_automaton(&grammar));
+
+        assert!(owned.to_automaton(&other).is_none());
+    }
+
+    #[test]
+    fn items_with_multi_byte_symbols_are_displayed() {
+        let grammar = create_grammar_from_str("S -> Expr a Expr . Expr -> b .").unwrap();
+        let expr = grammar.get_symbol_by_name("Expr").unwrap();
+        let production = grammar
+            .productions
+            .iter()
+            .find(|p| p.driver == 'S')
+            .unwrap();
+        let item = Lr0Item {
+            production,
+            dot_position: 2,
+        };
+
+        assert_eq!(item.to_string(), format!("S -> {expr}a•{expr}"));
+    }
+}
@@ -0,0 +1,472 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+use crate::grammar::{
+    grammar::Grammar,
+    notation::Notation,
+    parse_structs::{Action, FirstFollowSet, Production},
+};
+use crate::lr0::{AutomatonEdge, get_edge_map, get_sorted_edges};
+use crate::output::{
+    AutomatonView,
+    dot::{self, DotStyle},
+};
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Lr1Item<'a> {
+    pub production: &'a Production,
+    dot_position: usize,
+    pub lookahead: char,
+}
+
+impl<'a> Lr1Item<'a> {
+    pub fn new(production: &'a Production, lookahead: char) -> Self {
+        Lr1Item {
+            production,
+            dot_position: 0,
+            lookahead,
+        }
+    }
+
+    pub fn dot_position(&self) -> usize {
+        self.dot_position
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.dot_position >= self.production.body.len()
+    }
+
+    pub fn next_symbol(&self) -> Option<char> {
+        self.production.body.get(self.dot_position).copied()
+    }
+
+    pub fn next_item(&self) -> Option<Lr1Item<'a>> {
+        if !self.is_complete() {
+            Some(Lr1Item {
+                production: self.production,
+                dot_position: self.dot_position + 1,
+                lookahead: self.lookahead,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// The LR(0) core of the item, i.e. the production index (`None` for the augmented starting
+    /// production) and the dot position.
+    pub fn core(&self) -> (Option<usize>, usize) {
+        (self.production.index, self.dot_position)
+    }
+
+    /// Formats the LR(0) core of the item using the symbol names of the grammar it belongs to
+    pub fn format_core(&self, grammar: &Grammar, notation: &Notation) -> String {
+        notation.item(grammar, self.production, self.dot_position)
+    }
+}
+
+impl std::fmt::Display for Lr1Item<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (before_dot, after_dot) = self.production.body.split_at(self.dot_position);
+        write!(
+            f,
+            "[{} -> {}•{}, {}]",
+            self.production.driver,
+            before_dot.iter().collect::<String>(),
+            after_dot.iter().collect::<String>(),
+            self.lookahead
+        )
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct Lr1AutomatonNode<'a> {
+    pub kernel: Vec<Lr1Item<'a>>,
+    pub closure: Vec<Lr1Item<'a>>,
+}
+
+impl<'a> Lr1AutomatonNode<'a> {
+    pub fn get_generated_kernel(&self, by_char: char) -> Vec<Lr1Item<'a>> {
+        self.kernel
+            .iter()
+            .chain(self.closure.iter())
+            .filter(|item| item.next_symbol() == Some(by_char))
+            .map(|item| item.next_item().unwrap())
+            .collect()
+    }
+
+    /// Returns the set of LR(0) cores of the kernel items. Two LR(1) states with the same kernel
+    /// core are merged by the LALR(1) construction.
+    pub fn kernel_core(&self) -> Vec<(Option<usize>, usize)> {
+        let mut core: Vec<(Option<usize>, usize)> =
+            self.kernel.iter().map(|item| item.core()).collect();
+        core.sort();
+        core.dedup();
+        core
+    }
+
+    fn key(&self) -> Vec<(Option<usize>, usize, char)> {
+        let mut key: Vec<(Option<usize>, usize, char)> = self
+            .kernel
+            .iter()
+            .map(|item| (item.production.index, item.dot_position, item.lookahead))
+            .collect();
+        key.sort();
+        key
+    }
+}
+
+/// An LR(1) item which doesn't borrow the grammar, identified by the index of its production
+/// (`None` for the augmented starting production), the dot position and the lookahead
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Lr1ItemOwned {
+    pub production: Option<usize>,
+    pub dot_position: usize,
+    pub lookahead: char,
+}
+
+impl From<&Lr1Item<'_>> for Lr1ItemOwned {
+    fn from(item: &Lr1Item<'_>) -> Self {
+        Lr1ItemOwned {
+            production: item.production.index,
+            dot_position: item.dot_position,
+            lookahead: item.lookahead,
+        }
+    }
+}
+
+impl Lr1ItemOwned {
+    /// The production of the item in the given grammar, `None` if the grammar has no such
+    /// production
+    pub fn production<'a>(&self, grammar: &'a Grammar) -> Option<&'a Production> {
+        match self.production {
+            Some(index) => grammar.productions.get(index),
+            None => grammar.starting_prod.as_ref(),
+        }
+    }
+
+    /// The item borrowing the production from the given grammar, `None` if the grammar has no
+    /// such production or its body is shorter than the dot position
+    pub fn to_item<'a>(&self, grammar: &'a Grammar) -> Option<Lr1Item<'a>> {
+        let production = self.production(grammar)?;
+        (self.dot_position <= production.body.len()).then_some(Lr1Item {
+            production,
+            dot_position: self.dot_position,
+            lookahead: self.lookahead,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lr1AutomatonNodeOwned {
+    pub kernel: Vec<Lr1ItemOwned>,
+    pub closure: Vec<Lr1ItemOwned>,
+}
+
+impl From<&Lr1AutomatonNode<'_>> for Lr1AutomatonNodeOwned {
+    fn from(node: &Lr1AutomatonNode<'_>) -> Self {
+        Lr1AutomatonNodeOwned {
+            kernel: node.kernel.iter().map(Lr1ItemOwned::from).collect(),
+            closure: node.closure.iter().map(Lr1ItemOwned::from).collect(),
+        }
+    }
+}
+
+impl Lr1AutomatonNodeOwned {
+    /// The state borrowing the productions from the given grammar, `None` if any of its items
+    /// doesn't belong to it
+    pub fn to_node<'a>(&self, grammar: &'a Grammar) -> Option<Lr1AutomatonNode<'a>> {
+        let to_items = |items: &[Lr1ItemOwned]| -> Option<Vec<Lr1Item<'a>>> {
+            items.iter().map(|item| item.to_item(grammar)).collect()
+        };
+        Some(Lr1AutomatonNode {
+            kernel: to_items(&self.kernel)?,
+            closure: to_items(&self.closure)?,
+        })
+    }
+}
+
+/// An LR(1) automaton which doesn't borrow the grammar, with the edges sorted by source and
+/// target state, so that it can be cached apart from the grammar it was built from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lr1AutomatonOwned {
+    pub nodes: Vec<Lr1AutomatonNodeOwned>,
+    pub edges: Vec<AutomatonEdge>,
+}
+
+impl From<&Lr1Automaton<'_>> for Lr1AutomatonOwned {
+    fn from(automaton: &Lr1Automaton<'_>) -> Self {
+        Lr1AutomatonOwned {
+            nodes: automaton
+                .nodes
+                .iter()
+                .map(Lr1AutomatonNodeOwned::from)
+                .collect(),
+            edges: get_sorted_edges(&automaton.edges),
+        }
+    }
+}
+
+impl From<Lr1Automaton<'_>> for Lr1AutomatonOwned {
+    fn from(automaton: Lr1Automaton<'_>) -> Self {
+        Lr1AutomatonOwned::from(&automaton)
+    }
+}
+
+impl Lr1AutomatonOwned {
+    /// The automaton borrowing the productions from the given grammar, which must be the one
+    /// it was built from: `None` if any of its items doesn't belong to it
+    pub fn to_automaton<'a>(&self, grammar: &'a Grammar) -> Option<Lr1Automaton<'a>> {
+        Some(Lr1Automaton {
+            grammar,
+            nodes: self
+                .nodes
+                .iter()
+                .map(|node| node.to_node(grammar))
+                .collect::<Option<Vec<Lr1AutomatonNode>>>()?,
+            edges: get_edge_map(&self.edges),
+        })
+    }
+}
+
+pub struct Lr1Automaton<'a> {
+    pub grammar: &'a Grammar,
+    pub nodes: Vec<Lr1AutomatonNode<'a>>,
+    pub edges: HashMap<usize, Vec<(usize, char)>>,
+}
+
+impl Lr1Automaton<'_> {
+    /// Cores of the items of every state taking part in a conflict of the given LR(1) parsing
+    /// table, so that the cells solved by precedence are not reported. Whether an item
+    /// conflicts only depends on its core, not on its lookahead.
+    pub fn get_conflicting_cores(
+        &self,
+        parsing_table: &[HashMap<char, Vec<Action>>],
+    ) -> Vec<HashSet<(Option<usize>, usize)>> {
+        self.nodes
+            .iter()
+            .zip(parsing_table.iter())
+            .map(|(node, row)| {
+                node.kernel
+                    .iter()
+                    .chain(node.closure.iter())
+                    .filter(|item| {
+                        self.grammar
+                            .is_conflicting_item(row, item.production, item.dot_position)
+                    })
+                    .map(Lr1Item::core)
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Returns the automaton in DOT notation, with the same layout used for the LR(0) one. Items
+    /// sharing the same core are written once, followed by all of their lookaheads. The states
+    /// with conflicts in the given parsing table are drawn with a red border.
+    pub fn generate_dot_notation_string(
+        &self,
+        parsing_table: &[HashMap<char, Vec<Action>>],
+        notation: &Notation,
+        style: &DotStyle,
+    ) -> String {
+        dot::render_automaton(
+            &AutomatonView::from_lr1(self, parsing_table, notation),
+            style,
+        )
+    }
+
+    /// Formats the items as `core, a/b`, merging the lookaheads of the items sharing the same
+    /// core
+    pub fn format_items(&self, items: &[Lr1Item], notation: &Notation) -> Vec<String> {
+        let mut cores: Vec<(String, Vec<String>)> = vec![];
+        for item in items {
+            let core = item.format_core(self.grammar, notation);
+            let lookahead = self.grammar.get_symbol_name(item.lookahead);
+            match cores.iter_mut().find(|(c, _)| *c == core) {
+                Some((_, lookaheads)) => lookaheads.push(lookahead),
+                None => cores.push((core, vec![lookahead])),
+            }
+        }
+        for (_, lookaheads) in cores.iter_mut() {
+            lookaheads.sort();
+        }
+        cores
+            .iter()
+            .map(|(core, lookaheads)| format!("{}, {}", core, lookaheads.join("/")))
+            .collect()
+    }
+}
+
+impl<'a> std::fmt::Display for Lr1Automaton<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, node) in self.nodes.iter().enumerate() {
+            writeln!(f, "Node {}:", index)?;
+            writeln!(f, "  Kernel:")?;
+            for item in &node.kernel {
+                writeln!(f, "    {}", item)?;
+            }
+            writeln!(f, "  Closure:")?;
+            for item in &node.closure {
+                writeln!(f, "    {}", item)?;
+            }
+        }
+        writeln!(f, "Edges:")?;
+        for (from, edges) in &self.edges {
+            for (to, by_char) in edges {
+                writeln!(f, "  {} --{}--> {}", from, by_char, to)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Grammar {
+    /// Computes the LR(1) closure of the given items: for every item `[A -> α•Bβ, a]` the items
+    /// `[B -> •γ, b]` are added for each `b` in FIRST(βa). The returned vector does not contain
+    /// the starting items.
+    pub fn lr1_closure<'a>(
+        &'a self,
+        lr1_items: &[Lr1Item<'a>],
+        first_follow_set: &HashMap<char, FirstFollowSet>,
+    ) -> Vec<Lr1Item<'a>> {
+        let mut queue: VecDeque<Lr1Item<'a>> = lr1_items.iter().cloned().collect();
+        let mut seen: HashSet<Lr1Item<'a>> = lr1_items.iter().cloned().collect();
+        let mut rv = vec![];
+
+        while let Some(current_item) = queue.pop_front() {
+            let Some(next_symbol) = current_item.next_symbol() else {
+                continue;
+            };
+            if !first_follow_set.contains_key(&next_symbol) {
+                continue;
+            }
+
+            let mut rest = current_item.production.body[current_item.dot_position + 1..].to_vec();
+            rest.push(current_item.lookahead);
+            let (lookaheads, _) = self.first_of_sequence(&rest, first_follow_set);
+            let mut lookaheads: Vec<char> = lookaheads.into_iter().collect();
+            lookaheads.sort();
+
+            for production in self
+                .productions
+                .iter()
+                .filter(|prod| prod.driver == next_symbol)
+            {
+                for lookahead in lookaheads.iter() {
+                    let next_item = Lr1Item::new(production, *lookahead);
+                    if seen.insert(next_item.clone()) {
+                        queue.push_back(next_item.clone());
+                        rv.push(next_item);
+                    }
+                }
+            }
+        }
+
+        rv
+    }
+}
+
+/// Returns the canonical LR(1) automaton for the given grammar. The automaton of a grammar
+/// without productions has no states.
+pub fn get_lr1_parsing_automaton<'a>(
+    grammar: &'a Grammar,
+    first_follow_set: &HashMap<char, FirstFollowSet>,
+) -> Lr1Automaton<'a> {
+    let Some(starting_prod) = grammar.starting_prod.as_ref() else {
+        return Lr1Automaton {
+            grammar,
+            nodes: vec![],
+            edges: HashMap::new(),
+        };
+    };
+    let starting_item = Lr1Item::new(starting_prod, '$');
+    let first_node = Lr1AutomatonNode {
+        kernel: vec![starting_item.clone()],
+        closure: grammar.lr1_closure(&[starting_item], first_follow_set),
+    };
+
+    let mut kernels: HashMap<Vec<(Option<usize>, usize, char)>, usize> = HashMap::new();
+    kernels.insert(first_node.key(), 0);
+
+    let mut automaton = Lr1Automaton {
+        grammar,
+        nodes: vec![first_node],
+        edges: HashMap::new(),
+    };
+
+    let sorted_symbols = [grammar.get_sorted_terms(), grammar.get_sorted_non_terms()].concat();
+    let mut nodes_to_process = VecDeque::from([0]);
+
+    while let Some(curr_node_index) = nodes_to_process.pop_front() {
+        for outgoing_char in sorted_symbols.iter() {
+            let next_kernel = automaton.nodes[curr_node_index].get_generated_kernel(*outgoing_char);
+            if next_kernel.is_empty() {
+                continue;
+            }
+
+            let new_node = Lr1AutomatonNode {
+                closure: grammar.lr1_closure(&next_kernel, first_follow_set),
+                kernel: next_kernel,
+            };
+            let key = new_node.key();
+            let to = match kernels.get(&key) {
+                Some(index) => *index,
+                None => {
+                    let index = automaton.nodes.len();
+                    kernels.insert(key, index);
+                    automaton.nodes.push(new_node);
+                    nodes_to_process.push_back(index);
+                    index
+                }
+            };
+
+            automaton
+                .edges
+                .entry(curr_node_index)
+                .or_default()
+                .push((to, *outgoing_char));
+        }
+    }
+
+    automaton
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grammar::create_grammar::create_grammar_from_str;
+    use crate::lr0::get_sorted_edges;
+
+    #[test]
+    fn owned_automaton_round_trips_through_serde() {
+        let grammar = create_grammar_from_str("S -> C C . C -> c C | d .").unwrap();
+        let automaton = get_lr1_parsing_automaton(&grammar, &grammar.get_first_follow_table());
+
+        let json = serde_json::to_string(&Lr1AutomatonOwned::from(&automaton)).unwrap();
+        let owned: Lr1AutomatonOwned = serde_json::from_str(&json).unwrap();
+        let restored = owned.to_automaton(&grammar).unwrap();
+
+        assert!(restored.nodes == automaton.nodes);
+        assert_eq!(
+            get_sorted_edges(&restored.edges),
+            get_sorted_edges(&automaton.edges)
+        );
+    }
+
+    #[test]
+    fn conflicts_solved_by_precedence_are_not_reported() {
+        let grammar = create_grammar_from_str("%left '+'\nE -> E + E | a .").unwrap();
+        let automaton = get_lr1_parsing_automaton(&grammar, &grammar.get_first_follow_table());
+        let mut parsing_table = grammar.get_lr1_parsing_table_from(&automaton);
+        let has_conflicts = |parsing_table: &[HashMap<char, Vec<Action>>]| {
+            automaton
+                .get_conflicting_cores(parsing_table)
+                .iter()
+                .any(|cores| !cores.is_empty())
+        };
+
+        assert!(has_conflicts(&parsing_table));
+        grammar.resolve_conflicts(&mut parsing_table);
+        assert!(!has_conflicts(&parsing_table));
+    }
+}
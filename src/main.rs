@@ -1,37 +1,128 @@
-#![allow(unused)]
-pub mod args;
-pub mod grammar;
-pub mod lr0;
-pub mod util;
-
-use base64::{Engine as _, engine::general_purpose};
 use clap::Parser;
-use lr0::{get_parsing_automaton, print_closures};
-use std::fs;
-use std::io::{self, Read};
+use free_lang_util::lr0::get_parsing_automaton;
+use std::io::{self, Write};
+use std::process::ExitCode;
+
+use free_lang_util::args::Args;
+use free_lang_util::args::command::{AutomatonKind, TransformOp};
+use free_lang_util::args::error::{ArgsError, InitError};
+use free_lang_util::args::finalized::{FinalizedArgs, OutputType};
+use free_lang_util::grammar::create_grammar::decode_grammar;
+use free_lang_util::grammar::grammar::Grammar;
+use free_lang_util::grammar::parse_structs::{ParsingTable, ParsingTableKind};
+use free_lang_util::item_nfa::ItemNfa;
+use free_lang_util::lr1::get_lr1_parsing_automaton;
+use free_lang_util::output::{Artifact, get_backend};
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        // clap prints the usage (or the help) and picks the exit code by itself
+        Err(InitError::ArgsError(ArgsError::ArgsParsingError(err))) => err.exit(),
+        Err(err) => {
+            eprintln!("{}", err);
+            err.exit_code()
+        }
+    }
+}
+
+fn run() -> Result<(), InitError> {
+    let args = Args::try_parse().map_err(ArgsError::ArgsParsingError)?;
+    let finalized_args = args.finalize()?;
 
-use args::Args;
-use grammar::create_grammar::decode_grammar;
+    let grammar = decode_grammar(&finalized_args.input_type)?;
+    // println!("Decoded Grammar:\n{}", grammar);
+    let output = generate_output(&grammar, &finalized_args)?;
 
-fn main() {
-    let args = args::Args::parse();
-    let finalized_args = args.finalize();
+    let mut stdout = io::stdout().lock();
+    match writeln!(stdout, "{}", output).and_then(|_| stdout.flush()) {
+        // The output being piped into a command which does not read all of it (e.g. `head`)
+        // is not an error
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        result => Ok(result?),
+    }
+}
 
-    match decode_grammar(&args) {
-        Ok(grammar) => {
-            // println!("Decoded Grammar:\n{}", grammar);
-            if args.latex {
-                println!(
-                    "{}",
-                    grammar.generate_latex_string(finalized_args.latex_format_descriptor.unwrap())
-                );
-            } else if args.dot {
-                let automaton = get_parsing_automaton(&grammar);
-                println!("{}", automaton.generate_dot_notation_string());
+/// Generates the requested output with the backend of the given format
+fn generate_output(grammar: &Grammar, finalized_args: &FinalizedArgs) -> Result<String, InitError> {
+    let FinalizedArgs {
+        output_type,
+        format,
+        render_options,
+        ..
+    } = finalized_args;
+    let backend = get_backend(*format, render_options);
+    let output = match output_type {
+        OutputType::Report(descriptor) => backend.report(grammar, descriptor),
+        OutputType::Tables(kinds) => {
+            let first_follow_set = grammar.get_first_follow_table();
+            let parsing_tables: Vec<ParsingTable> = kinds
+                .iter()
+                .map(|kind| grammar.get_parsing_table(*kind, Some(&first_follow_set)))
+                .collect();
+            // The reduce actions of the LR tables refer to the productions by their number
+            let mut artifacts = vec![];
+            if kinds.iter().any(|kind| *kind != ParsingTableKind::Ll1) {
+                artifacts.push(Artifact::Productions);
             }
+            artifacts.extend(
+                kinds
+                    .iter()
+                    .zip(parsing_tables.iter())
+                    .map(|(kind, parsing_table)| Artifact::ParsingTable(*kind, parsing_table)),
+            );
+            backend.render_all(grammar, &artifacts)
         }
-        Err(err) => {
-            eprintln!("Error decoding grammar: {:?}", err);
+        OutputType::Automaton(AutomatonKind::Lr0) => {
+            let automaton = get_parsing_automaton(grammar);
+            backend.render_all(grammar, &[Artifact::Lr0Automaton(&automaton)])
         }
-    }
+        OutputType::Automaton(AutomatonKind::Lr1) => {
+            let automaton = get_lr1_parsing_automaton(grammar, &grammar.get_first_follow_table());
+            let mut parsing_table = grammar.get_lr1_parsing_table_from(&automaton);
+            grammar.resolve_conflicts(&mut parsing_table);
+            backend.render_all(
+                grammar,
+                &[Artifact::Lr1Automaton(&automaton, &parsing_table)],
+            )
+        }
+        OutputType::Automaton(AutomatonKind::ItemNfa) => {
+            let construction = ItemNfa::new(grammar).subset_construction();
+            backend.render_all(grammar, &[Artifact::SubsetConstruction(&construction)])
+        }
+        OutputType::FirstFollow => {
+            let first_follow_set = grammar.get_first_follow_table();
+            backend.render_all(grammar, &[Artifact::FirstFollow(&first_follow_set)])
+        }
+        OutputType::Parse { sentence, table } => {
+            let trace = grammar.simulate_parsing(sentence, *table);
+            backend.render_all(grammar, &[Artifact::ParseTrace(&trace)])
+        }
+        OutputType::Transform(op) => {
+            let transformed = match op {
+                // EBNF constructs are desugared while reading the grammar
+                TransformOp::Desugar => grammar.clone(),
+                TransformOp::RemoveLeftRecursion => grammar.remove_left_recursion()?,
+                TransformOp::LeftFactor => grammar.left_factor(),
+            };
+            backend.render_all(&transformed, &[Artifact::Grammar])
+        }
+        OutputType::Classify => {
+            let classification = grammar.classify();
+            backend.render_all(grammar, &[Artifact::Classification(&classification)])
+        }
+        OutputType::Generate { count, max_length } => {
+            let sentences = grammar.generate_sentences(*count, *max_length);
+            backend.render_all(grammar, &[Artifact::Sentences(&sentences)])
+        }
+    };
+
+    output.ok_or_else(|| {
+        ArgsError::ArgsConflict(format!(
+            "`{}` can't be written as {}",
+            output_type.get_command_name(),
+            format
+        ))
+        .into()
+    })
 }
@@ -0,0 +1,379 @@
+use std::collections::HashMap;
+
+use super::{AutomatonView, OutputBackend, StateView};
+use crate::grammar::{
+    classify::GrammarClassification,
+    grammar::Grammar,
+    notation::Notation,
+    parse_structs::{Action, FirstFollowSet, ParsingTable, ParsingTableKind},
+    simulate::ParseTrace,
+};
+use crate::item_nfa::SubsetConstruction;
+use crate::lr0::Lr0Automaton;
+use crate::lr1::Lr1Automaton;
+
+/// Graphviz DOT, only available for the automata
+pub struct DotBackend {
+    pub notation: Notation,
+    pub style: DotStyle,
+}
+
+/// Layout and labels of the automata written in DOT. The default draws the states top to bottom
+/// as records with their kernel and closure, as the Graphviz link of the report does.
+#[derive(Debug, Clone, Default)]
+pub struct DotStyle {
+    /// Label the states with their kernel only, the closure being implied
+    pub kernel_only: bool,
+    /// Lay out the automaton left to right instead of top to bottom
+    pub left_to_right: bool,
+    /// Draw the accepting states, the ones holding `S' -> S•`, with a bold border (a double one
+    /// with HTML-like labels)
+    pub mark_accepting: bool,
+    /// Add an edge by `$` from the accepting states to an `accept` node
+    pub accept_edge: bool,
+    /// Highlight the complete items, which the parser reduces by
+    pub highlight_reduces: bool,
+    /// Write HTML-like labels instead of records, which can style single items
+    pub html_labels: bool,
+}
+
+/// Escapes the characters which delimit the fields of a record label (or end the quoted string)
+fn escape_record(text: &str) -> String {
+    let mut rv = String::new();
+    for c in text.chars() {
+        if matches!(c, '{' | '}' | '|' | '<' | '>' | '"' | '\\') {
+            rv.push('\\');
+        }
+        rv.push(c);
+    }
+    rv
+}
+
+/// Escapes the text of an HTML-like label
+fn escape_html_label(text: &str) -> String {
+    let mut rv = String::new();
+    for c in text.chars() {
+        match c {
+            '&' => rv.push_str("&amp;"),
+            '<' => rv.push_str("&lt;"),
+            '>' => rv.push_str("&gt;"),
+            '"' => rv.push_str("&quot;"),
+            _ => rv.push(c),
+        }
+    }
+    rv
+}
+
+/// Escapes the characters ending a quoted string
+fn escape_quoted(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Sections of the label of a state: its kernel and, unless `kernel_only`, its closure
+fn get_sections<'a>(state: &'a StateView, style: &DotStyle) -> Vec<&'a [String]> {
+    let mut sections = vec![state.kernel.as_slice()];
+    if !style.kernel_only && !state.closure.is_empty() {
+        sections.push(state.closure.as_slice());
+    }
+    sections
+}
+
+/// A record split into the name of the state and its sections, one item per line. Records
+/// can't style single items, so the states with conflicts have a red border and, when the
+/// reduces are highlighted, the states holding complete items are filled.
+fn format_record(state: &StateView, style: &DotStyle) -> String {
+    let mut fields = vec![escape_record(&state.name)];
+    for section in get_sections(state, style) {
+        fields.push(
+            section
+                .iter()
+                .map(|item| format!("{}\\n", escape_record(&item.replace("->", "→"))))
+                .collect::<String>(),
+        );
+    }
+    // Left to right the fields of a record are laid out side by side, unless flipped by braces
+    let label = if style.left_to_right {
+        fields.join(" | ")
+    } else {
+        format!("{{ {} }}", fields.join(" | "))
+    };
+
+    let mut attributes = vec![format!("label=\"{}\"", label)];
+    let mut styles = vec![];
+    if style.mark_accepting && state.accepting {
+        styles.push("bold");
+    }
+    if style.highlight_reduces && !state.reduces.is_empty() {
+        styles.push("filled");
+        attributes.push(String::from("fillcolor=lightyellow"));
+    }
+    if !styles.is_empty() {
+        attributes.push(format!("style=\"{}\"", styles.join(",")));
+    }
+    if state.has_conflicts() {
+        attributes.push(String::from("color=red, penwidth=2"));
+    }
+    attributes.join(", ")
+}
+
+/// A table with a row for the name of the state and one for each section, the conflicting items
+/// being underlined and (when highlighted) the complete ones bold and blue
+fn format_html_label(state: &StateView, style: &DotStyle) -> String {
+    let color = if state.has_conflicts() {
+        "red"
+    } else {
+        "black"
+    };
+    // A double border is drawn by a table within a table
+    let (border, outer) = if style.mark_accepting && state.accepting {
+        (
+            "2",
+            (
+                format!(
+                    "<TABLE BORDER=\"1\" CELLBORDER=\"0\" CELLPADDING=\"2\" COLOR=\"{}\"><TR><TD>",
+                    color
+                ),
+                "</TD></TR></TABLE>",
+            ),
+        )
+    } else {
+        (
+            if state.has_conflicts() { "2" } else { "1" },
+            (String::new(), ""),
+        )
+    };
+
+    let mut rows = vec![format!(
+        "<TR><TD>{}</TD></TR>",
+        escape_html_label(&state.name)
+    )];
+    for section in get_sections(state, style) {
+        let items: Vec<String> = section
+            .iter()
+            .map(|item| {
+                let mut text = escape_html_label(&item.replace("->", "→"));
+                if state.conflicts.contains(item) {
+                    text = format!("<U>{}</U>", text);
+                }
+                if style.highlight_reduces && state.reduces.contains(item) {
+                    text = format!("<FONT COLOR=\"blue\"><B>{}</B></FONT>", text);
+                }
+                text
+            })
+            .collect();
+        rows.push(format!(
+            "<TR><TD BORDER=\"1\" SIDES=\"T\" ALIGN=\"LEFT\" BALIGN=\"LEFT\">{}</TD></TR>",
+            items.join("<BR/>")
+        ));
+    }
+    format!(
+        "label=<{}<TABLE BORDER=\"{}\" CELLBORDER=\"0\" CELLSPACING=\"0\" COLOR=\"{}\">{}</TABLE>{}>",
+        outer.0,
+        border,
+        color,
+        rows.concat(),
+        outer.1
+    )
+}
+
+/// Renders the automaton in DOT notation with the given style. Every symbol of a transition is
+/// written as an edge of its own.
+pub fn render_automaton(automaton: &AutomatonView, style: &DotStyle) -> String {
+    let mut rv = String::from("digraph G {\n");
+    if style.left_to_right {
+        rv.push_str("rankdir=LR\n");
+    }
+    if style.html_labels {
+        rv.push_str("node[shape=plaintext]\n\n");
+    } else {
+        rv.push_str("node[shape=record]\n\n");
+    }
+    for (index, state) in automaton.states.iter().enumerate() {
+        let attributes = if style.html_labels {
+            format_html_label(state, style)
+        } else {
+            format_record(state, style)
+        };
+        rv.push_str(&format!("{} [{}]\n", index, attributes));
+    }
+    if style.accept_edge {
+        rv.push_str("accept [shape=plaintext, label=\"accept\"]\n");
+    }
+
+    rv.push_str("\n\n//nodes\n");
+    for (from, symbol, to) in automaton.edges.iter() {
+        rv.push_str(&format!(
+            "{} -> {} [label=\"{}\"]\n",
+            from,
+            to,
+            escape_quoted(symbol)
+        ));
+    }
+    if style.accept_edge {
+        for (index, state) in automaton.states.iter().enumerate() {
+            if state.accepting {
+                rv.push_str(&format!("{} -> accept [label=\"$\"]\n", index));
+            }
+        }
+    }
+    rv.push_str("}\n");
+    rv
+}
+
+impl OutputBackend for DotBackend {
+    fn grammar(&self, _grammar: &Grammar) -> Option<String> {
+        None
+    }
+
+    fn productions(&self, _grammar: &Grammar) -> Option<String> {
+        None
+    }
+
+    fn first_follow(
+        &self,
+        _grammar: &Grammar,
+        _first_follow_set: &HashMap<char, FirstFollowSet>,
+    ) -> Option<String> {
+        None
+    }
+
+    fn parsing_table(
+        &self,
+        _grammar: &Grammar,
+        _kind: ParsingTableKind,
+        _parsing_table: &ParsingTable,
+    ) -> Option<String> {
+        None
+    }
+
+    fn lr0_automaton(&self, automaton: &Lr0Automaton) -> Option<String> {
+        Some(automaton.generate_dot_notation_string(&self.notation, &self.style))
+    }
+
+    fn lr1_automaton(
+        &self,
+        automaton: &Lr1Automaton,
+        parsing_table: &[HashMap<char, Vec<Action>>],
+    ) -> Option<String> {
+        Some(automaton.generate_dot_notation_string(parsing_table, &self.notation, &self.style))
+    }
+
+    fn item_sets(&self, _automaton: &Lr0Automaton) -> Option<String> {
+        None
+    }
+
+    fn goto_table(&self, _automaton: &Lr0Automaton) -> Option<String> {
+        None
+    }
+
+    /// The item NFA, the subset construction being left to the other formats
+    fn subset_construction(
+        &self,
+        _grammar: &Grammar,
+        construction: &SubsetConstruction,
+    ) -> Option<String> {
+        Some(
+            construction
+                .nfa
+                .generate_dot_notation_string(&self.notation),
+        )
+    }
+
+    fn parse_trace(&self, _grammar: &Grammar, _trace: &ParseTrace) -> Option<String> {
+        None
+    }
+
+    fn classification(
+        &self,
+        _grammar: &Grammar,
+        _classification: &GrammarClassification,
+    ) -> Option<String> {
+        None
+    }
+
+    fn sentences(&self, _grammar: &Grammar, _sentences: &[Vec<char>]) -> Option<String> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grammar::create_grammar::create_grammar_from_str;
+    use crate::lr0::get_parsing_automaton;
+
+    /// The LR(0) automaton, in DOT, of a grammar whose terminals Graphviz would interpret
+    fn render(style: DotStyle) -> String {
+        let grammar = create_grammar_from_str("S -> '|' S '{' | '<' .").unwrap();
+        let backend = DotBackend {
+            notation: Notation::default(),
+            style,
+        };
+        backend
+            .lr0_automaton(&get_parsing_automaton(&grammar))
+            .unwrap()
+    }
+
+    #[test]
+    fn labels_are_escaped() {
+        assert_eq!(escape_record("{a|b}<\"\\"), "\\{a\\|b\\}\\<\\\"\\\\");
+        assert_eq!(
+            escape_html_label("<a & \"b\">"),
+            "&lt;a &amp; &quot;b&quot;&gt;"
+        );
+    }
+
+    #[test]
+    fn record_labels_escape_the_terminals() {
+        let dot = render(DotStyle::default());
+
+        assert!(dot.contains("node[shape=record]"));
+        assert!(dot.contains("0 [label=\"{ s0 | @ → •S\\n | S → •\\|S\\{\\nS → •\\<\\n }\"]"));
+        assert!(dot.contains("4 [label=\"{ s4 | S → \\|S•\\{\\n }\"]"));
+        // The labels of the edges are quoted strings, where only `"` and `\` are escaped
+        assert!(dot.contains("0 -> 3 [label=\"|\"]"));
+        assert!(dot.contains("4 -> 5 [label=\"{\"]"));
+    }
+
+    #[test]
+    fn html_labels_escape_the_terminals() {
+        let dot = render(DotStyle {
+            html_labels: true,
+            ..DotStyle::default()
+        });
+
+        assert!(dot.contains("node[shape=plaintext]"));
+        assert!(dot.contains("S → •|S{<BR/>S → •&lt;</TD>"));
+        assert!(dot.contains("<TD>s2</TD></TR><TR><TD BORDER=\"1\" SIDES=\"T\" ALIGN=\"LEFT\" BALIGN=\"LEFT\">S → &lt;•</TD>"));
+        assert!(!dot.contains("→ •<") && !dot.contains("→ <•"));
+    }
+
+    #[test]
+    fn style_options_are_applied() {
+        let dot = render(DotStyle {
+            kernel_only: true,
+            accept_edge: true,
+            mark_accepting: true,
+            ..DotStyle::default()
+        });
+
+        assert!(dot.contains("0 [label=\"{ s0 | @ → •S\\n }\"]\n"));
+        assert!(!dot.contains("S → •\\<"));
+        assert!(dot.contains("1 [label=\"{ s1 | @ → S•\\n }\", style=\"bold\"]\n"));
+        assert_eq!(dot.matches("style=\"bold\"").count(), 1);
+        assert!(dot.contains("accept [shape=plaintext, label=\"accept\"]\n"));
+        assert!(dot.contains("1 -> accept [label=\"$\"]\n"));
+        assert_eq!(dot.matches("-> accept").count(), 1);
+
+        let dot = render(DotStyle {
+            mark_accepting: true,
+            html_labels: true,
+            ..DotStyle::default()
+        });
+        // With HTML-like labels the accepting state has a double border instead
+        assert!(dot.contains(
+            "1 [label=<<TABLE BORDER=\"1\" CELLBORDER=\"0\" CELLPADDING=\"2\" COLOR=\"black\"><TR><TD><TABLE BORDER=\"2\""
+        ));
+        assert!(!dot.contains("accept ["));
+    }
+}
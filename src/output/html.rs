@@ -0,0 +1,237 @@
+use std::collections::HashMap;
+
+use super::{AutomatonView, OutputBackend, Table, get_rules, svg};
+use crate::grammar::{
+    classify::GrammarClassification,
+    grammar::Grammar,
+    notation::Notation,
+    parse_structs::{Action, FirstFollowSet, ParsingTable, ParsingTableKind},
+    simulate::ParseTrace,
+};
+use crate::item_nfa::SubsetConstruction;
+use crate::lr0::Lr0Automaton;
+use crate::lr1::Lr1Automaton;
+
+/// A single HTML page, styled inline and with the automata embedded as SVG, so that it can be
+/// opened offline without any other file
+pub struct HtmlBackend {
+    pub notation: Notation,
+}
+
+const STYLE: &str = "body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; margin-bottom: 2em; }
+caption { font-weight: bold; margin-bottom: 0.5em; }
+th, td { border: 1px solid #999; padding: 0.2em 0.6em; text-align: center; }
+td.conflict { background: #f8d0d0; color: #a00000; font-weight: bold; }
+pre { background: #f4f4f4; padding: 1em; }
+figure { margin: 0 0 2em 0; overflow-x: auto; }
+figcaption { font-weight: bold; margin-top: 0.5em; }";
+
+pub fn escape_html(text: &str) -> String {
+    let mut rv = String::new();
+    for c in text.chars() {
+        match c {
+            '&' => rv.push_str("&amp;"),
+            '<' => rv.push_str("&lt;"),
+            '>' => rv.push_str("&gt;"),
+            '"' => rv.push_str("&quot;"),
+            _ => rv.push(c),
+        }
+    }
+    rv
+}
+
+impl HtmlBackend {
+    /// The notes on the cells are shown as tooltips
+    fn format_table(table: &Table) -> String {
+        let mut rv = String::new();
+        rv.push_str("<table>\n");
+        rv.push_str(&format!(
+            "<caption>{}</caption>\n",
+            escape_html(&table.caption)
+        ));
+        rv.push_str(&format!(
+            "<tr>{}</tr>\n",
+            table
+                .header
+                .iter()
+                .map(|cell| format!("<th>{}</th>", escape_html(cell)))
+                .collect::<String>()
+        ));
+        for (row_index, row) in table.rows.iter().enumerate() {
+            rv.push_str(&format!(
+                "<tr>{}</tr>\n",
+                row.iter()
+                    .enumerate()
+                    .map(|(column, cell)| {
+                        let mut attributes = String::new();
+                        if table.conflicts.contains(&(row_index, column)) {
+                            attributes.push_str(" class=\"conflict\"");
+                        }
+                        if let Some(note) = table.notes.get(&(row_index, column)) {
+                            attributes.push_str(&format!(" title=\"{}\"", escape_html(note)));
+                        }
+                        format!("<td{}>{}</td>", attributes, escape_html(cell))
+                    })
+                    .collect::<String>()
+            ));
+        }
+        rv.push_str("</table>");
+        rv
+    }
+
+    /// The automaton is embedded as an SVG image, so that the page needs no other file
+    fn format_automaton(automaton: &AutomatonView) -> String {
+        format!(
+            "<figure>\n{}\n<figcaption>{}</figcaption>\n</figure>",
+            svg::render_automaton(automaton),
+            escape_html(&automaton.title)
+        )
+    }
+}
+
+impl OutputBackend for HtmlBackend {
+    fn grammar(&self, grammar: &Grammar) -> Option<String> {
+        let rules: Vec<String> = get_rules(grammar, &self.notation)
+            .iter()
+            .map(|(driver, bodies)| {
+                escape_html(&format!(
+                    "{} {} {}",
+                    driver,
+                    self.notation.arrow,
+                    bodies.join(" | ")
+                ))
+            })
+            .collect();
+        Some(format!(
+            "<figure>\n<pre>{}</pre>\n<figcaption>Grammar</figcaption>\n</figure>",
+            rules.join("\n")
+        ))
+    }
+
+    fn productions(&self, grammar: &Grammar) -> Option<String> {
+        Some(Self::format_table(&Table::from_productions(
+            grammar,
+            &self.notation,
+        )))
+    }
+
+    fn first_follow(
+        &self,
+        grammar: &Grammar,
+        first_follow_set: &HashMap<char, FirstFollowSet>,
+    ) -> Option<String> {
+        Some(Self::format_table(&Table::from_first_follow(
+            grammar,
+            first_follow_set,
+        )))
+    }
+
+    fn parsing_table(
+        &self,
+        grammar: &Grammar,
+        kind: ParsingTableKind,
+        parsing_table: &ParsingTable,
+    ) -> Option<String> {
+        Some(Self::format_table(&Table::from_parsing_table(
+            grammar,
+            kind,
+            parsing_table,
+            &self.notation,
+        )))
+    }
+
+    fn lr0_automaton(&self, automaton: &Lr0Automaton) -> Option<String> {
+        Some(Self::format_automaton(&AutomatonView::from_lr0(
+            automaton,
+            &self.notation,
+        )))
+    }
+
+    fn lr1_automaton(
+        &self,
+        automaton: &Lr1Automaton,
+        parsing_table: &[HashMap<char, Vec<Action>>],
+    ) -> Option<String> {
+        Some(Self::format_automaton(&AutomatonView::from_lr1(
+            automaton,
+            parsing_table,
+            &self.notation,
+        )))
+    }
+
+    fn item_sets(&self, automaton: &Lr0Automaton) -> Option<String> {
+        let automaton = AutomatonView::from_lr0(automaton, &self.notation);
+        let sets: Vec<String> = automaton
+            .states
+            .iter()
+            .map(|state| escape_html(&state.format_item_set()))
+            .collect();
+        Some(format!(
+            "<figure>\n<pre>{}</pre>\n<figcaption>LR(0) item sets</figcaption>\n</figure>",
+            sets.join("\n")
+        ))
+    }
+
+    fn goto_table(&self, automaton: &Lr0Automaton) -> Option<String> {
+        Some(Self::format_table(&Table::from_goto(
+            automaton.grammar,
+            &AutomatonView::from_lr0(automaton, &self.notation),
+        )))
+    }
+
+    fn subset_construction(
+        &self,
+        _grammar: &Grammar,
+        _construction: &SubsetConstruction,
+    ) -> Option<String> {
+        None
+    }
+
+    fn parse_trace(&self, grammar: &Grammar, trace: &ParseTrace) -> Option<String> {
+        Some(Self::format_table(&Table::from_parse_trace(
+            grammar,
+            trace,
+            &self.notation,
+        )))
+    }
+
+    fn classification(
+        &self,
+        grammar: &Grammar,
+        classification: &GrammarClassification,
+    ) -> Option<String> {
+        Some(Self::format_table(&Table::from_classification(
+            grammar,
+            classification,
+            &self.notation,
+        )))
+    }
+
+    fn sentences(&self, grammar: &Grammar, sentences: &[Vec<char>]) -> Option<String> {
+        let items: String = sentences
+            .iter()
+            .map(|sentence| {
+                format!(
+                    "<li><code>{}</code></li>\n",
+                    escape_html(&grammar.format_sentence(sentence))
+                )
+            })
+            .collect();
+        Some(format!("<ul>\n{}</ul>", items))
+    }
+
+    /// The automaton takes the place of the link to Graphviz of the LaTeX report
+    fn reports_automaton(&self) -> bool {
+        true
+    }
+
+    fn document(&self, _grammar: &Grammar, parts: Vec<String>) -> String {
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>free-lang-util</title>\n<style>\n{}\n</style>\n</head>\n<body>\n{}\n</body>\n</html>",
+            STYLE,
+            parts.join("\n")
+        )
+    }
+}
@@ -0,0 +1,228 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use super::OutputBackend;
+use crate::grammar::{
+    classify::GrammarClassification,
+    grammar::Grammar,
+    parse_structs::{Action, FirstFollowSet, ParsingTable, ParsingTableKind, Production},
+    simulate::ParseTrace,
+};
+use crate::item_nfa::{NfaEdge, SubsetConstruction, SubsetStep};
+use crate::lr0::{
+    AutomatonEdge, Lr0Automaton, Lr0AutomatonNodeOwned, Lr0AutomatonOwned, Lr0ItemOwned,
+    get_sorted_edges,
+};
+use crate::lr1::{Lr1Automaton, Lr1AutomatonOwned};
+
+/// Version of the schema of the JSON output, to be bumped on every incompatible change
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// JSON with a versioned schema. Symbols are written as their character, whose name can be
+/// looked up in the `symbols` list of the document.
+pub struct JsonBackend;
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum SymbolKind {
+    Terminal,
+    NonTerminal,
+    EndOfInput,
+    AugmentedStart,
+}
+
+#[derive(Serialize)]
+struct Symbol {
+    id: char,
+    name: String,
+    kind: SymbolKind,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+enum JsonArtifact<'a> {
+    Grammar(&'a Grammar),
+    /// Listed by their index, the value of the reduce actions
+    Productions(&'a [Production]),
+    FirstFollow(BTreeMap<char, &'a FirstFollowSet>),
+    ParsingTable {
+        kind: ParsingTableKind,
+        table: &'a ParsingTable,
+    },
+    Lr0Automaton(Lr0AutomatonOwned),
+    Lr1Automaton(Lr1AutomatonOwned),
+    /// The states of the LR(0) automaton, listed by their index
+    ItemSets(Vec<Lr0AutomatonNodeOwned>),
+    /// The transitions of the LR(0) automaton, sorted by source and target state
+    GotoTable(Vec<AutomatonEdge>),
+    /// The states of the DFA and the steps refer to the states of the NFA by their index in
+    /// `items`
+    SubsetConstruction {
+        items: Vec<Lr0ItemOwned>,
+        nfa_edges: &'a [NfaEdge],
+        states: &'a [Vec<usize>],
+        edges: &'a [AutomatonEdge],
+        steps: &'a [SubsetStep],
+        lr0_states: &'a [Option<usize>],
+    },
+    ParseTrace(&'a ParseTrace),
+    Classification(&'a GrammarClassification),
+    Sentences(&'a [Vec<char>]),
+}
+
+#[derive(Serialize)]
+struct Document {
+    schema_version: u32,
+    symbols: Vec<Symbol>,
+    artifacts: Vec<Value>,
+}
+
+impl JsonBackend {
+    fn to_string(artifact: JsonArtifact) -> Option<String> {
+        serde_json::to_string(&artifact).ok()
+    }
+
+    /// Every symbol which may appear in the artifacts, the special ones included
+    fn get_symbols(grammar: &Grammar) -> Vec<Symbol> {
+        let mut rv = vec![Symbol {
+            id: '@',
+            name: "@".to_string(),
+            kind: SymbolKind::AugmentedStart,
+        }];
+        rv.extend(
+            grammar
+                .get_sorted_non_terms()
+                .into_iter()
+                .map(|symbol| Symbol {
+                    id: symbol,
+                    name: grammar.get_symbol_name(symbol),
+                    kind: SymbolKind::NonTerminal,
+                }),
+        );
+        rv.extend(grammar.get_sorted_terms().into_iter().map(|symbol| Symbol {
+            id: symbol,
+            name: grammar.get_symbol_name(symbol),
+            kind: SymbolKind::Terminal,
+        }));
+        rv.push(Symbol {
+            id: '$',
+            name: "$".to_string(),
+            kind: SymbolKind::EndOfInput,
+        });
+        rv
+    }
+}
+
+impl OutputBackend for JsonBackend {
+    fn grammar(&self, grammar: &Grammar) -> Option<String> {
+        Self::to_string(JsonArtifact::Grammar(grammar))
+    }
+
+    fn productions(&self, grammar: &Grammar) -> Option<String> {
+        Self::to_string(JsonArtifact::Productions(&grammar.productions))
+    }
+
+    fn first_follow(
+        &self,
+        _grammar: &Grammar,
+        first_follow_set: &HashMap<char, FirstFollowSet>,
+    ) -> Option<String> {
+        Self::to_string(JsonArtifact::FirstFollow(
+            first_follow_set
+                .iter()
+                .map(|(symbol, set)| (*symbol, set))
+                .collect(),
+        ))
+    }
+
+    fn parsing_table(
+        &self,
+        _grammar: &Grammar,
+        kind: ParsingTableKind,
+        parsing_table: &ParsingTable,
+    ) -> Option<String> {
+        Self::to_string(JsonArtifact::ParsingTable {
+            kind,
+            table: parsing_table,
+        })
+    }
+
+    fn lr0_automaton(&self, automaton: &Lr0Automaton) -> Option<String> {
+        Self::to_string(JsonArtifact::Lr0Automaton(automaton.into()))
+    }
+
+    fn lr1_automaton(
+        &self,
+        automaton: &Lr1Automaton,
+        _parsing_table: &[HashMap<char, Vec<Action>>],
+    ) -> Option<String> {
+        Self::to_string(JsonArtifact::Lr1Automaton(automaton.into()))
+    }
+
+    fn item_sets(&self, automaton: &Lr0Automaton) -> Option<String> {
+        Self::to_string(JsonArtifact::ItemSets(
+            Lr0AutomatonOwned::from(automaton).nodes,
+        ))
+    }
+
+    fn goto_table(&self, automaton: &Lr0Automaton) -> Option<String> {
+        Self::to_string(JsonArtifact::GotoTable(get_sorted_edges(&automaton.edges)))
+    }
+
+    fn subset_construction(
+        &self,
+        _grammar: &Grammar,
+        construction: &SubsetConstruction,
+    ) -> Option<String> {
+        Self::to_string(JsonArtifact::SubsetConstruction {
+            items: construction
+                .nfa
+                .items
+                .iter()
+                .map(Lr0ItemOwned::from)
+                .collect(),
+            nfa_edges: &construction.nfa.edges,
+            states: &construction.states,
+            edges: &construction.edges,
+            steps: &construction.steps,
+            lr0_states: &construction.lr0_states,
+        })
+    }
+
+    fn parse_trace(&self, _grammar: &Grammar, trace: &ParseTrace) -> Option<String> {
+        Self::to_string(JsonArtifact::ParseTrace(trace))
+    }
+
+    fn classification(
+        &self,
+        _grammar: &Grammar,
+        classification: &GrammarClassification,
+    ) -> Option<String> {
+        Self::to_string(JsonArtifact::Classification(classification))
+    }
+
+    fn sentences(&self, _grammar: &Grammar, sentences: &[Vec<char>]) -> Option<String> {
+        Self::to_string(JsonArtifact::Sentences(sentences))
+    }
+
+    /// The front ends draw the automaton on their own, so it replaces the link to Graphviz
+    fn reports_automaton(&self) -> bool {
+        true
+    }
+
+    /// The artifacts are wrapped in a single object together with the schema version and the
+    /// symbol table
+    fn document(&self, grammar: &Grammar, parts: Vec<String>) -> String {
+        let document = Document {
+            schema_version: SCHEMA_VERSION,
+            symbols: Self::get_symbols(grammar),
+            artifacts: parts
+                .iter()
+                .map(|part| serde_json::from_str(part).unwrap())
+                .collect(),
+        };
+        serde_json::to_string_pretty(&document).unwrap()
+    }
+}
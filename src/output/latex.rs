@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+
+use super::{AutomatonView, OutputBackend, tikz};
+use crate::grammar::{
+    classify::GrammarClassification,
+    grammar::Grammar,
+    latex::{
+        LatexFormatOutputFormatDescriptor, LatexStyle, escape_latex,
+        generate_latex_section_heading, get_parsing_table_label, wrap_latex_document,
+    },
+    parse_structs::{Action, FirstFollowSet, ParsingTable, ParsingTableKind},
+    simulate::ParseTrace,
+};
+use crate::item_nfa::SubsetConstruction;
+use crate::lr0::Lr0Automaton;
+use crate::lr1::Lr1Automaton;
+
+/// LaTeX fragments, relying on the `booktabs`, `float` and `hyperref` packages, or a complete
+/// document loading them
+pub struct LatexBackend {
+    pub standalone: bool,
+    pub style: LatexStyle,
+}
+
+impl LatexBackend {
+    /// In a complete document every artifact is a section with its own heading, labelled
+    /// `sec:<label>`
+    fn section(&self, heading: &str, label: &str, body: String) -> Option<String> {
+        if self.standalone {
+            Some(format!(
+                "{}{}",
+                generate_latex_section_heading(heading, label),
+                body
+            ))
+        } else {
+            Some(body)
+        }
+    }
+}
+
+impl OutputBackend for LatexBackend {
+    fn grammar(&self, grammar: &Grammar) -> Option<String> {
+        self.section(
+            self.style.language.grammar(),
+            "grammar",
+            grammar
+                .generate_grammar_latex(&self.style.notation)
+                .trim_end()
+                .to_string(),
+        )
+    }
+
+    fn productions(&self, grammar: &Grammar) -> Option<String> {
+        self.section(
+            self.style.language.productions(),
+            "productions",
+            grammar.generate_productions_latex(&self.style),
+        )
+    }
+
+    fn first_follow(
+        &self,
+        grammar: &Grammar,
+        first_follow_set: &HashMap<char, FirstFollowSet>,
+    ) -> Option<String> {
+        self.section(
+            self.style.language.first_follow_sets(),
+            "first-follow",
+            grammar.generate_first_follow_latex(Some(first_follow_set), &self.style),
+        )
+    }
+
+    fn parsing_table(
+        &self,
+        grammar: &Grammar,
+        kind: ParsingTableKind,
+        parsing_table: &ParsingTable,
+    ) -> Option<String> {
+        self.section(
+            &self.style.language.parsing_table(kind),
+            &get_parsing_table_label(kind),
+            grammar.generate_parsing_table_kind_latex(kind, parsing_table, &self.style),
+        )
+    }
+
+    fn lr0_automaton(&self, automaton: &Lr0Automaton) -> Option<String> {
+        let caption = self.style.language.automaton("LR(0)");
+        self.section(
+            &caption,
+            "lr0-automaton",
+            tikz::render_figure(
+                &AutomatonView::from_lr0(automaton, &self.style.notation),
+                &caption,
+                "lr0-automaton",
+            ),
+        )
+    }
+
+    fn lr1_automaton(
+        &self,
+        automaton: &Lr1Automaton,
+        parsing_table: &[HashMap<char, Vec<Action>>],
+    ) -> Option<String> {
+        let caption = self.style.language.automaton("LR(1)");
+        self.section(
+            &caption,
+            "lr1-automaton",
+            tikz::render_figure(
+                &AutomatonView::from_lr1(automaton, parsing_table, &self.style.notation),
+                &caption,
+                "lr1-automaton",
+            ),
+        )
+    }
+
+    fn item_sets(&self, automaton: &Lr0Automaton) -> Option<String> {
+        self.section(
+            self.style.language.item_sets(),
+            "item-sets",
+            automaton
+                .grammar
+                .generate_item_sets_latex(automaton, &self.style)
+                .trim_end()
+                .to_string(),
+        )
+    }
+
+    fn goto_table(&self, automaton: &Lr0Automaton) -> Option<String> {
+        self.section(
+            self.style.language.goto_function(),
+            "goto",
+            automaton
+                .grammar
+                .generate_goto_table_latex(automaton, &self.style),
+        )
+    }
+
+    fn subset_construction(
+        &self,
+        _grammar: &Grammar,
+        _construction: &SubsetConstruction,
+    ) -> Option<String> {
+        None
+    }
+
+    fn parse_trace(&self, grammar: &Grammar, trace: &ParseTrace) -> Option<String> {
+        self.section(
+            &self.style.language.parsing_of(trace.kind, &trace.sentence),
+            "parse-trace",
+            trace.generate_latex_string(grammar, &self.style.notation, self.style.language),
+        )
+    }
+
+    fn classification(
+        &self,
+        grammar: &Grammar,
+        classification: &GrammarClassification,
+    ) -> Option<String> {
+        self.section(
+            self.style.language.classification(),
+            "classification",
+            classification.generate_latex_string(
+                grammar,
+                &self.style.notation,
+                self.style.language,
+            ),
+        )
+    }
+
+    fn sentences(&self, grammar: &Grammar, sentences: &[Vec<char>]) -> Option<String> {
+        let items: String = sentences
+            .iter()
+            .map(|sentence| {
+                format!(
+                    "\\item \\texttt{{{}}}\n",
+                    escape_latex(&grammar.format_sentence(sentence))
+                )
+            })
+            .collect();
+        self.section(
+            self.style.language.sentences(),
+            "sentences",
+            format!("\\begin{{itemize}}\n{}\\end{{itemize}}", items),
+        )
+    }
+
+    fn document(&self, _grammar: &Grammar, parts: Vec<String>) -> String {
+        if self.standalone {
+            wrap_latex_document(&parts.join("\n\n"))
+        } else {
+            parts.join("\n\n")
+        }
+    }
+
+    /// The LaTeX report also links the grammar and the automaton to the online tools
+    fn report(
+        &self,
+        grammar: &Grammar,
+        descriptor: &LatexFormatOutputFormatDescriptor,
+    ) -> Option<String> {
+        if self.standalone {
+            Some(grammar.generate_latex_document(descriptor.clone(), &self.style))
+        } else {
+            Some(grammar.generate_latex_string(descriptor.clone(), &self.style))
+        }
+    }
+}
@@ -0,0 +1,321 @@
+use std::collections::{HashMap, VecDeque};
+
+/// Number of sweeps of the barycenter heuristic ordering the layers
+const ORDER_SWEEPS: usize = 8;
+/// Number of sweeps moving the vertices towards their neighbours
+const PLACE_SWEEPS: usize = 4;
+
+/// A vertex of the layered graph: a node of the graph, or one of the dummy vertices an edge
+/// spanning several layers is routed through, identified by the index of the edge and the step
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Vertex {
+    Node(usize),
+    Dummy(usize, usize),
+}
+
+/// A graph split into layers, with the vertices of every layer ordered so that the edges
+/// between consecutive layers cross as little as possible
+pub struct LayeredGraph {
+    /// Layer of every node
+    pub layers: Vec<usize>,
+    /// Vertices of every layer, in order
+    pub ranks: Vec<Vec<Vertex>>,
+    /// Dummy vertices every edge goes through, from its source to its target
+    pub routes: Vec<Vec<Vertex>>,
+    /// Pairs of vertices of consecutive layers joined by (a part of) an edge, the vertex of the
+    /// lower layer first
+    segments: Vec<(Vertex, Vertex)>,
+}
+
+/// Coordinates of the vertices of a `LayeredGraph`, with layers going left to right
+pub struct Placement {
+    /// Center of every vertex
+    pub centers: HashMap<Vertex, (f64, f64)>,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Distance of every node from the first one, which is the layer the node is drawn in
+pub fn get_layers(node_count: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+    let mut layers = vec![usize::MAX; node_count];
+    let mut queue = VecDeque::from([0]);
+    if node_count > 0 {
+        layers[0] = 0;
+    }
+    while let Some(node) = queue.pop_front() {
+        for (_, to) in edges.iter().filter(|(from, _)| *from == node) {
+            if layers[*to] == usize::MAX {
+                layers[*to] = layers[node] + 1;
+                queue.push_back(*to);
+            }
+        }
+    }
+    // Nodes not reachable from the first one are drawn in the first layer
+    layers
+        .into_iter()
+        .map(|layer| if layer == usize::MAX { 0 } else { layer })
+        .collect()
+}
+
+impl LayeredGraph {
+    /// Layers the graph in the style of Sugiyama: nodes are assigned to layers by their
+    /// distance from the first node, edges spanning more than two layers are split by dummy
+    /// vertices (backward edges being reversed) and the layers are ordered with the barycenter
+    /// heuristic, keeping the order with the fewest crossings.
+    pub fn new(node_count: usize, edges: &[(usize, usize)]) -> LayeredGraph {
+        let layers = get_layers(node_count, edges);
+        let layer_count = layers.iter().max().map_or(0, |max| max + 1);
+
+        let mut ranks: Vec<Vec<Vertex>> = vec![vec![]; layer_count];
+        for (node, layer) in layers.iter().enumerate() {
+            ranks[*layer].push(Vertex::Node(node));
+        }
+        let mut routes = vec![];
+        let mut segments: Vec<(Vertex, Vertex)> = vec![];
+        for (index, (from, to)) in edges.iter().enumerate() {
+            let (low, high) = if layers[*from] <= layers[*to] {
+                (*from, *to)
+            } else {
+                (*to, *from)
+            };
+            if layers[low] == layers[high] {
+                routes.push(vec![]);
+                continue;
+            }
+            let dummies: Vec<Vertex> = (1..layers[high] - layers[low])
+                .map(|step| Vertex::Dummy(index, step))
+                .collect();
+            for (step, dummy) in dummies.iter().enumerate() {
+                ranks[layers[low] + step + 1].push(*dummy);
+            }
+            let chain: Vec<Vertex> = std::iter::once(Vertex::Node(low))
+                .chain(dummies.iter().copied())
+                .chain(std::iter::once(Vertex::Node(high)))
+                .collect();
+            segments.extend(chain.windows(2).map(|pair| (pair[0], pair[1])));
+            let mut route = dummies;
+            if low != *from {
+                route.reverse();
+            }
+            routes.push(route);
+        }
+
+        let mut best_ranks = ranks.clone();
+        let mut best_crossings = count_crossings(&ranks, &segments);
+        for sweep in 0..ORDER_SWEEPS {
+            if sweep % 2 == 0 {
+                for layer in 1..layer_count {
+                    reorder(&mut ranks, layer, &segments, true);
+                }
+            } else {
+                for layer in (0..layer_count.saturating_sub(1)).rev() {
+                    reorder(&mut ranks, layer, &segments, false);
+                }
+            }
+            let crossings = count_crossings(&ranks, &segments);
+            if crossings < best_crossings {
+                best_ranks = ranks.clone();
+                best_crossings = crossings;
+            }
+        }
+
+        LayeredGraph {
+            layers,
+            ranks: best_ranks,
+            routes,
+            segments,
+        }
+    }
+
+    /// Places the vertices given the sizes of the nodes, dummies taking no room. Every layer is
+    /// as wide as its widest node; the vertices of a layer are stacked in order and moved
+    /// towards the average height of their neighbours.
+    pub fn place(&self, sizes: &[(f64, f64)], layer_gap: f64, node_gap: f64) -> Placement {
+        let size = |vertex: &Vertex| match vertex {
+            Vertex::Node(node) => sizes[*node],
+            Vertex::Dummy(_, _) => (0.0, 0.0),
+        };
+        let mut neighbours: HashMap<Vertex, Vec<Vertex>> = HashMap::new();
+        for (low, high) in self.segments.iter() {
+            neighbours.entry(*low).or_default().push(*high);
+            neighbours.entry(*high).or_default().push(*low);
+        }
+
+        // Vertical centers, starting from the layers stacked from the top
+        let mut centers_y: HashMap<Vertex, f64> = HashMap::new();
+        for rank in self.ranks.iter() {
+            let mut y = 0.0;
+            for vertex in rank.iter() {
+                let height = size(vertex).1;
+                centers_y.insert(*vertex, y + height / 2.0);
+                y += height + node_gap;
+            }
+        }
+        for sweep in 0..PLACE_SWEEPS {
+            let layers: Vec<usize> = if sweep % 2 == 0 {
+                (0..self.ranks.len()).collect()
+            } else {
+                (0..self.ranks.len()).rev().collect()
+            };
+            for layer in layers {
+                let rank = &self.ranks[layer];
+                let desired: Vec<f64> = rank
+                    .iter()
+                    .map(|vertex| match neighbours.get(vertex) {
+                        Some(others) if !others.is_empty() => {
+                            others.iter().map(|other| centers_y[other]).sum::<f64>()
+                                / others.len() as f64
+                        }
+                        _ => centers_y[vertex],
+                    })
+                    .collect();
+                // The vertices keep their order without overlapping, then the whole layer is
+                // shifted by the average distance from the desired heights
+                let mut placed = vec![];
+                let mut bottom = f64::NEG_INFINITY;
+                for (vertex, desired) in rank.iter().zip(desired.iter()) {
+                    let height = size(vertex).1;
+                    let center = (desired - height / 2.0).max(bottom + node_gap) + height / 2.0;
+                    bottom = center + height / 2.0;
+                    placed.push(center);
+                }
+                let shift = desired
+                    .iter()
+                    .zip(placed.iter())
+                    .map(|(desired, placed)| desired - placed)
+                    .sum::<f64>()
+                    / rank.len().max(1) as f64;
+                for (vertex, center) in rank.iter().zip(placed.iter()) {
+                    centers_y.insert(*vertex, center + shift);
+                }
+            }
+        }
+
+        let top = centers_y
+            .iter()
+            .map(|(vertex, y)| y - size(vertex).1 / 2.0)
+            .fold(f64::INFINITY, f64::min);
+        let top = if top.is_finite() { top } else { 0.0 };
+        let mut centers = HashMap::new();
+        let mut x = 0.0;
+        let mut height: f64 = 0.0;
+        for rank in self.ranks.iter() {
+            let width = rank.iter().map(|vertex| size(vertex).0).fold(0.0, f64::max);
+            for vertex in rank.iter() {
+                let y = centers_y[vertex] - top;
+                height = height.max(y + size(vertex).1 / 2.0);
+                centers.insert(*vertex, (x + width / 2.0, y));
+            }
+            x += width + layer_gap;
+        }
+        Placement {
+            centers,
+            width: (x - layer_gap).max(0.0),
+            height,
+        }
+    }
+}
+
+/// Sorts a layer by the barycenter of the positions of its neighbours in the previous (or the
+/// next) layer, vertices without neighbours keeping their position
+fn reorder(ranks: &mut [Vec<Vertex>], layer: usize, segments: &[(Vertex, Vertex)], down: bool) {
+    let fixed_layer = if down { layer - 1 } else { layer + 1 };
+    let positions: HashMap<Vertex, usize> = ranks[fixed_layer]
+        .iter()
+        .enumerate()
+        .map(|(position, vertex)| (*vertex, position))
+        .collect();
+    let mut barycenters: Vec<(f64, Vertex)> = ranks[layer]
+        .iter()
+        .enumerate()
+        .map(|(position, vertex)| {
+            let neighbours: Vec<usize> = segments
+                .iter()
+                .filter_map(|(low, high)| {
+                    let (own, other) = if down { (high, low) } else { (low, high) };
+                    (own == vertex).then(|| positions.get(other)).flatten()
+                })
+                .copied()
+                .collect();
+            if neighbours.is_empty() {
+                (position as f64, *vertex)
+            } else {
+                (
+                    neighbours.iter().sum::<usize>() as f64 / neighbours.len() as f64,
+                    *vertex,
+                )
+            }
+        })
+        .collect();
+    barycenters.sort_by(|a, b| a.0.total_cmp(&b.0));
+    ranks[layer] = barycenters.into_iter().map(|(_, vertex)| vertex).collect();
+}
+
+/// Number of pairs of segments crossing between consecutive layers
+fn count_crossings(ranks: &[Vec<Vertex>], segments: &[(Vertex, Vertex)]) -> usize {
+    let positions: HashMap<Vertex, (usize, usize)> = ranks
+        .iter()
+        .enumerate()
+        .flat_map(|(layer, rank)| {
+            rank.iter()
+                .enumerate()
+                .map(move |(position, vertex)| (*vertex, (layer, position)))
+        })
+        .collect();
+    let placed: Vec<(usize, usize, usize)> = segments
+        .iter()
+        .map(|(low, high)| {
+            let (layer, low_position) = positions[low];
+            (layer, low_position, positions[high].1)
+        })
+        .collect();
+    let mut rv = 0;
+    for (index, (layer, low, high)) in placed.iter().enumerate() {
+        for (other_layer, other_low, other_high) in placed[index + 1..].iter() {
+            if layer == other_layer
+                && (*low as isize - *other_low as isize) * (*high as isize - *other_high as isize)
+                    < 0
+            {
+                rv += 1;
+            }
+        }
+    }
+    rv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cyclic_graphs_are_layered_by_distance() {
+        // 2 -> 0 goes back two layers, 1 -> 1 is a self loop and 3 is unreachable
+        let edges = [(0, 1), (1, 2), (2, 0), (2, 1), (1, 1)];
+        let graph = LayeredGraph::new(4, &edges);
+
+        assert_eq!(graph.layers, vec![0, 1, 2, 0]);
+        assert_eq!(graph.routes[2], vec![Vertex::Dummy(2, 1)]);
+        assert!(graph.ranks[1].contains(&Vertex::Dummy(2, 1)));
+        for edge in [0, 1, 3, 4] {
+            assert!(graph.routes[edge].is_empty());
+        }
+        let mut first_layer = graph.ranks[0].clone();
+        first_layer.sort();
+        assert_eq!(first_layer, vec![Vertex::Node(0), Vertex::Node(3)]);
+    }
+
+    #[test]
+    fn crossings_are_reduced() {
+        // Stacked by index, 1 -> 4 and 2 -> 3 cross
+        let edges = [(0, 1), (0, 2), (1, 4), (2, 3)];
+        let graph = LayeredGraph::new(5, &edges);
+        let by_index = vec![
+            vec![Vertex::Node(0)],
+            vec![Vertex::Node(1), Vertex::Node(2)],
+            vec![Vertex::Node(3), Vertex::Node(4)],
+        ];
+
+        assert_eq!(count_crossings(&by_index, &graph.segments), 1);
+        assert_eq!(count_crossings(&graph.ranks, &graph.segments), 0);
+    }
+}
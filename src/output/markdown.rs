@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+
+use super::{AutomatonView, OutputBackend, Table, get_rules};
+use crate::grammar::{
+    classify::GrammarClassification,
+    grammar::Grammar,
+    notation::Notation,
+    parse_structs::{Action, FirstFollowSet, ParsingTable, ParsingTableKind},
+    simulate::ParseTrace,
+};
+use crate::item_nfa::SubsetConstruction;
+use crate::lr0::Lr0Automaton;
+use crate::lr1::Lr1Automaton;
+
+/// GitHub flavoured Markdown
+pub struct MarkdownBackend {
+    pub notation: Notation,
+}
+
+/// Escapes the characters which would be interpreted as Markdown (or as the end of a cell). `$`
+/// is escaped too, since GitHub renders the text between two of them as math.
+fn escape_markdown(text: &str) -> String {
+    let mut rv = String::new();
+    for c in text.chars() {
+        if "\\`*_[]<|#$~".contains(c) {
+            rv.push('\\');
+        }
+        rv.push(c);
+    }
+    rv
+}
+
+impl MarkdownBackend {
+    /// Conflicting cells are written in bold
+    fn format_table(table: &Table) -> String {
+        let format_row = |row_index: Option<usize>, row: &[String]| {
+            format!(
+                "| {} |",
+                row.iter()
+                    .enumerate()
+                    .map(|(column, cell)| match row_index {
+                        Some(row_index) if table.conflicts.contains(&(row_index, column)) => {
+                            format!("**{}**", escape_markdown(cell))
+                        }
+                        _ => escape_markdown(cell),
+                    })
+                    .collect::<Vec<String>>()
+                    .join(" | ")
+            )
+        };
+
+        let mut lines = vec![
+            format!("**{}**", escape_markdown(&table.caption)),
+            String::new(),
+        ];
+        lines.push(format_row(None, &table.header));
+        lines.push(format!("|{}", " :---: |".repeat(table.header.len())));
+        lines.extend(
+            table
+                .rows
+                .iter()
+                .enumerate()
+                .map(|(row_index, row)| format_row(Some(row_index), row)),
+        );
+        lines.join("\n")
+    }
+
+    fn format_automaton(automaton: &AutomatonView) -> String {
+        let states = Table {
+            caption: format!("{} states", automaton.title),
+            header: ["State", "Kernel", "Closure"].map(String::from).to_vec(),
+            rows: automaton
+                .states
+                .iter()
+                .map(|state| {
+                    vec![
+                        state.name.clone(),
+                        state.kernel.join("; "),
+                        state.closure.join("; "),
+                    ]
+                })
+                .collect(),
+            conflicts: vec![],
+            notes: HashMap::new(),
+        };
+        let edges = Table {
+            caption: format!("{} transitions", automaton.title),
+            header: ["From", "Symbol", "To"].map(String::from).to_vec(),
+            rows: automaton
+                .edges
+                .iter()
+                .map(|(from, symbol, to)| {
+                    vec![
+                        automaton.states[*from].name.clone(),
+                        symbol.clone(),
+                        automaton.states[*to].name.clone(),
+                    ]
+                })
+                .collect(),
+            conflicts: vec![],
+            notes: HashMap::new(),
+        };
+        format!(
+            "{}\n\n{}",
+            Self::format_table(&states),
+            Self::format_table(&edges)
+        )
+    }
+}
+
+impl OutputBackend for MarkdownBackend {
+    fn grammar(&self, grammar: &Grammar) -> Option<String> {
+        let rules: Vec<String> = get_rules(grammar, &self.notation)
+            .iter()
+            .map(|(driver, bodies)| {
+                format!("{} {} {}", driver, self.notation.arrow, bodies.join(" | "))
+            })
+            .collect();
+        Some(format!("**Grammar**\n\n```\n{}\n```", rules.join("\n")))
+    }
+
+    fn productions(&self, grammar: &Grammar) -> Option<String> {
+        Some(Self::format_table(&Table::from_productions(
+            grammar,
+            &self.notation,
+        )))
+    }
+
+    fn first_follow(
+        &self,
+        grammar: &Grammar,
+        first_follow_set: &HashMap<char, FirstFollowSet>,
+    ) -> Option<String> {
+        Some(Self::format_table(&Table::from_first_follow(
+            grammar,
+            first_follow_set,
+        )))
+    }
+
+    fn parsing_table(
+        &self,
+        grammar: &Grammar,
+        kind: ParsingTableKind,
+        parsing_table: &ParsingTable,
+    ) -> Option<String> {
+        Some(Self::format_table(&Table::from_parsing_table(
+            grammar,
+            kind,
+            parsing_table,
+            &self.notation,
+        )))
+    }
+
+    fn lr0_automaton(&self, automaton: &Lr0Automaton) -> Option<String> {
+        Some(Self::format_automaton(&AutomatonView::from_lr0(
+            automaton,
+            &self.notation,
+        )))
+    }
+
+    fn lr1_automaton(
+        &self,
+        automaton: &Lr1Automaton,
+        parsing_table: &[HashMap<char, Vec<Action>>],
+    ) -> Option<String> {
+        Some(Self::format_automaton(&AutomatonView::from_lr1(
+            automaton,
+            parsing_table,
+            &self.notation,
+        )))
+    }
+
+    fn item_sets(&self, automaton: &Lr0Automaton) -> Option<String> {
+        let automaton = AutomatonView::from_lr0(automaton, &self.notation);
+        let sets: Vec<String> = automaton
+            .states
+            .iter()
+            .map(|state| state.format_item_set())
+            .collect();
+        Some(format!(
+            "**LR(0) item sets**\n\n```\n{}\n```",
+            sets.join("\n")
+        ))
+    }
+
+    fn goto_table(&self, automaton: &Lr0Automaton) -> Option<String> {
+        Some(Self::format_table(&Table::from_goto(
+            automaton.grammar,
+            &AutomatonView::from_lr0(automaton, &self.notation),
+        )))
+    }
+
+    fn subset_construction(
+        &self,
+        _grammar: &Grammar,
+        _construction: &SubsetConstruction,
+    ) -> Option<String> {
+        None
+    }
+
+    fn parse_trace(&self, grammar: &Grammar, trace: &ParseTrace) -> Option<String> {
+        Some(Self::format_table(&Table::from_parse_trace(
+            grammar,
+            trace,
+            &self.notation,
+        )))
+    }
+
+    fn classification(
+        &self,
+        grammar: &Grammar,
+        classification: &GrammarClassification,
+    ) -> Option<String> {
+        Some(Self::format_table(&Table::from_classification(
+            grammar,
+            classification,
+            &self.notation,
+        )))
+    }
+
+    fn sentences(&self, grammar: &Grammar, sentences: &[Vec<char>]) -> Option<String> {
+        Some(
+            sentences
+                .iter()
+                .map(|sentence| format!("- `{}`", grammar.format_sentence(sentence)))
+                .collect::<Vec<String>>()
+                .join("\n"),
+        )
+    }
+}
@@ -0,0 +1,199 @@
+use std::collections::{BTreeMap, HashMap};
+
+use super::{AutomatonView, OutputBackend};
+use crate::grammar::{
+    classify::GrammarClassification,
+    grammar::Grammar,
+    notation::Notation,
+    parse_structs::{Action, FirstFollowSet, ParsingTable, ParsingTableKind},
+    simulate::{ParseTrace, ParseTree},
+};
+use crate::item_nfa::SubsetConstruction;
+use crate::lr0::Lr0Automaton;
+use crate::lr1::Lr1Automaton;
+
+/// Mermaid flowcharts, rendered inline by GitLab, GitHub and most Markdown editors: the automata
+/// left to right and the parse tree of the sentence given to `parse` top down
+pub struct MermaidBackend {
+    pub notation: Notation,
+}
+
+/// Escapes the text of a quoted label, writing the characters Mermaid would interpret (`#`
+/// starting the entity codes included) as entity codes
+fn escape_mermaid(text: &str) -> String {
+    let mut rv = String::new();
+    for c in text.chars() {
+        match c {
+            '#' => rv.push_str("#35;"),
+            '"' => rv.push_str("#quot;"),
+            '<' => rv.push_str("#lt;"),
+            '>' => rv.push_str("#gt;"),
+            _ => rv.push(c),
+        }
+    }
+    rv
+}
+
+impl MermaidBackend {
+    /// Every state is labelled like the records of `generate_dot_notation_string`: its name,
+    /// its kernel and (if not empty) its closure, separated by a rule
+    fn format_automaton(automaton: &AutomatonView) -> String {
+        let mut lines = vec![String::from("flowchart LR")];
+        for (index, state) in automaton.states.iter().enumerate() {
+            let format_items = |items: &[String]| {
+                items
+                    .iter()
+                    .map(|item| escape_mermaid(&item.replace("->", "→")))
+                    .collect::<Vec<String>>()
+                    .join("<br/>")
+            };
+            let mut label = format!(
+                "{}<br/>───<br/>{}",
+                escape_mermaid(&state.name),
+                format_items(&state.kernel)
+            );
+            if !state.closure.is_empty() {
+                label.push_str(&format!("<br/>───<br/>{}", format_items(&state.closure)));
+            }
+            lines.push(format!("    q{}[\"{}\"]", index, label));
+        }
+
+        let mut merged_edges: BTreeMap<(usize, usize), Vec<&str>> = BTreeMap::new();
+        for (from, symbol, to) in automaton.edges.iter() {
+            merged_edges.entry((*from, *to)).or_default().push(symbol);
+        }
+        for ((from, to), symbols) in merged_edges.iter() {
+            lines.push(format!(
+                "    q{} -->|\"{}\"| q{}",
+                from,
+                escape_mermaid(&symbols.join(", ")),
+                to
+            ));
+        }
+        lines.join("\n")
+    }
+
+    /// Non terminals are drawn as rectangles, terminals (and the epsilon of the empty
+    /// productions) as rounded boxes
+    fn format_tree(&self, grammar: &Grammar, tree: &ParseTree) -> String {
+        let mut lines = vec![String::from("flowchart TD")];
+        let mut next_id = 0;
+        let mut stack = vec![(tree, next_id)];
+        lines.push(format!(
+            "    n0[\"{}\"]",
+            escape_mermaid(&grammar.get_symbol_name(tree.symbol))
+        ));
+        while let Some((node, id)) = stack.pop() {
+            let is_non_term = grammar.non_terms.contains(&node.symbol);
+            if is_non_term && node.children.is_empty() {
+                next_id += 1;
+                lines.push(format!(
+                    "    n{} --- n{}(\"{}\")",
+                    id,
+                    next_id,
+                    escape_mermaid(&self.notation.epsilon)
+                ));
+            }
+            let mut children = vec![];
+            for child in node.children.iter() {
+                next_id += 1;
+                let name = escape_mermaid(&grammar.get_symbol_name(child.symbol));
+                let shape = if grammar.non_terms.contains(&child.symbol) {
+                    format!("[\"{}\"]", name)
+                } else {
+                    format!("(\"{}\")", name)
+                };
+                lines.push(format!("    n{} --- n{}{}", id, next_id, shape));
+                children.push((child, next_id));
+            }
+            // Children are visited left to right
+            stack.extend(children.into_iter().rev());
+        }
+        lines.join("\n")
+    }
+}
+
+impl OutputBackend for MermaidBackend {
+    fn grammar(&self, _grammar: &Grammar) -> Option<String> {
+        None
+    }
+
+    fn productions(&self, _grammar: &Grammar) -> Option<String> {
+        None
+    }
+
+    fn first_follow(
+        &self,
+        _grammar: &Grammar,
+        _first_follow_set: &HashMap<char, FirstFollowSet>,
+    ) -> Option<String> {
+        None
+    }
+
+    fn parsing_table(
+        &self,
+        _grammar: &Grammar,
+        _kind: ParsingTableKind,
+        _parsing_table: &ParsingTable,
+    ) -> Option<String> {
+        None
+    }
+
+    fn lr0_automaton(&self, automaton: &Lr0Automaton) -> Option<String> {
+        Some(Self::format_automaton(&AutomatonView::from_lr0(
+            automaton,
+            &self.notation,
+        )))
+    }
+
+    fn lr1_automaton(
+        &self,
+        automaton: &Lr1Automaton,
+        parsing_table: &[HashMap<char, Vec<Action>>],
+    ) -> Option<String> {
+        Some(Self::format_automaton(&AutomatonView::from_lr1(
+            automaton,
+            parsing_table,
+            &self.notation,
+        )))
+    }
+
+    fn item_sets(&self, _automaton: &Lr0Automaton) -> Option<String> {
+        None
+    }
+
+    fn goto_table(&self, _automaton: &Lr0Automaton) -> Option<String> {
+        None
+    }
+
+    fn subset_construction(
+        &self,
+        _grammar: &Grammar,
+        _construction: &SubsetConstruction,
+    ) -> Option<String> {
+        None
+    }
+
+    /// The parse tree of an accepted sentence, or a single node with the outcome otherwise
+    fn parse_trace(&self, grammar: &Grammar, trace: &ParseTrace) -> Option<String> {
+        match &trace.tree {
+            Some(tree) => Some(self.format_tree(grammar, tree)),
+            None => Some(format!(
+                "flowchart TD\n    n0[\"{}\"]",
+                escape_mermaid(&trace.outcome.format(grammar, &self.notation))
+            )),
+        }
+    }
+
+    fn classification(
+        &self,
+        _grammar: &Grammar,
+        _classification: &GrammarClassification,
+    ) -> Option<String> {
+        None
+    }
+
+    fn sentences(&self, _grammar: &Grammar, _sentences: &[Vec<char>]) -> Option<String> {
+        None
+    }
+}
@@ -0,0 +1,752 @@
+pub mod dot;
+pub mod html;
+pub mod json;
+pub mod latex;
+pub mod layout;
+pub mod markdown;
+pub mod mermaid;
+pub mod svg;
+pub mod text;
+pub mod tikz;
+
+use std::collections::HashMap;
+
+use crate::args::command::{Format, TableStyle};
+use crate::grammar::{
+    classify::GrammarClassification,
+    grammar::Grammar,
+    latex::{LatexFormatOutputFormatDescriptor, LatexStyle},
+    locale::Language,
+    notation::Notation,
+    parse_structs::{Action, FirstFollowSet, ParsingTable, ParsingTableKind},
+    simulate::ParseTrace,
+};
+use crate::item_nfa::SubsetConstruction;
+use crate::lr0::{Lr0Automaton, Lr0Item, Lr0ItemOwned, get_parsing_automaton, get_sorted_edges};
+use crate::lr1::{Lr1Automaton, Lr1Item};
+use dot::DotStyle;
+
+/// Something the tool can produce, borrowing the data to render. Symbols are resolved through
+/// the grammar passed to the backend alongside the artifact.
+pub enum Artifact<'a> {
+    Grammar,
+    /// The productions numbered as in the reduce actions
+    Productions,
+    FirstFollow(&'a HashMap<char, FirstFollowSet>),
+    ParsingTable(ParsingTableKind, &'a ParsingTable),
+    Lr0Automaton(&'a Lr0Automaton<'a>),
+    /// The LR(1) automaton with its parsing table, the conflicts solved by precedence being no
+    /// longer marked
+    Lr1Automaton(&'a Lr1Automaton<'a>, &'a [HashMap<char, Vec<Action>>]),
+    /// The canonical collection of the LR(0) item sets, listed as `I = { kernel ; closure }`
+    ItemSets(&'a Lr0Automaton<'a>),
+    /// The GOTO function of the LR(0) automaton as a table, a column per grammar symbol
+    GotoTable(&'a Lr0Automaton<'a>),
+    /// The item NFA with the steps of the subset construction turning it into the LR(0)
+    /// automaton
+    SubsetConstruction(&'a SubsetConstruction<'a>),
+    ParseTrace(&'a ParseTrace),
+    Classification(&'a GrammarClassification),
+    Sentences(&'a [Vec<char>]),
+}
+
+/// Renders the artifacts in one output format. Every method returns `None` when the artifact
+/// can't be represented in the format (e.g. a parsing table in DOT).
+pub trait OutputBackend {
+    fn grammar(&self, grammar: &Grammar) -> Option<String>;
+
+    fn productions(&self, grammar: &Grammar) -> Option<String>;
+
+    fn first_follow(
+        &self,
+        grammar: &Grammar,
+        first_follow_set: &HashMap<char, FirstFollowSet>,
+    ) -> Option<String>;
+
+    fn parsing_table(
+        &self,
+        grammar: &Grammar,
+        kind: ParsingTableKind,
+        parsing_table: &ParsingTable,
+    ) -> Option<String>;
+
+    fn lr0_automaton(&self, automaton: &Lr0Automaton) -> Option<String>;
+
+    fn lr1_automaton(
+        &self,
+        automaton: &Lr1Automaton,
+        parsing_table: &[HashMap<char, Vec<Action>>],
+    ) -> Option<String>;
+
+    fn item_sets(&self, automaton: &Lr0Automaton) -> Option<String>;
+
+    fn goto_table(&self, automaton: &Lr0Automaton) -> Option<String>;
+
+    fn subset_construction(
+        &self,
+        grammar: &Grammar,
+        construction: &SubsetConstruction,
+    ) -> Option<String>;
+
+    fn parse_trace(&self, grammar: &Grammar, trace: &ParseTrace) -> Option<String>;
+
+    fn classification(
+        &self,
+        grammar: &Grammar,
+        classification: &GrammarClassification,
+    ) -> Option<String>;
+
+    fn sentences(&self, grammar: &Grammar, sentences: &[Vec<char>]) -> Option<String>;
+
+    /// Combines the rendered artifacts into the whole output
+    fn document(&self, _grammar: &Grammar, parts: Vec<String>) -> String {
+        parts.join("\n\n")
+    }
+
+    fn render(&self, grammar: &Grammar, artifact: &Artifact) -> Option<String> {
+        match artifact {
+            Artifact::Grammar => self.grammar(grammar),
+            Artifact::Productions => self.productions(grammar),
+            Artifact::FirstFollow(first_follow_set) => self.first_follow(grammar, first_follow_set),
+            Artifact::ParsingTable(kind, parsing_table) => {
+                self.parsing_table(grammar, *kind, parsing_table)
+            }
+            Artifact::Lr0Automaton(automaton) => self.lr0_automaton(automaton),
+            Artifact::Lr1Automaton(automaton, parsing_table) => {
+                self.lr1_automaton(automaton, parsing_table)
+            }
+            Artifact::ItemSets(automaton) => self.item_sets(automaton),
+            Artifact::GotoTable(automaton) => self.goto_table(automaton),
+            Artifact::SubsetConstruction(construction) => {
+                self.subset_construction(grammar, construction)
+            }
+            Artifact::ParseTrace(trace) => self.parse_trace(grammar, trace),
+            Artifact::Classification(classification) => {
+                self.classification(grammar, classification)
+            }
+            Artifact::Sentences(sentences) => self.sentences(grammar, sentences),
+        }
+    }
+
+    /// Renders every artifact and combines them, `None` if any of them is not supported
+    fn render_all(&self, grammar: &Grammar, artifacts: &[Artifact]) -> Option<String> {
+        let parts = artifacts
+            .iter()
+            .map(|artifact| self.render(grammar, artifact))
+            .collect::<Option<Vec<String>>>()?;
+        Some(self.document(grammar, parts))
+    }
+
+    /// Whether the report includes the LR(0) automaton in place of the link to Graphviz
+    fn reports_automaton(&self) -> bool {
+        false
+    }
+
+    /// Renders the sections of the report selected by the descriptor. The links to the online
+    /// tools are only part of the LaTeX report.
+    fn report(
+        &self,
+        grammar: &Grammar,
+        descriptor: &LatexFormatOutputFormatDescriptor,
+    ) -> Option<String> {
+        let first_follow_set = grammar.get_first_follow_table();
+        let lr0_parsing_table = grammar.get_parsing_table(ParsingTableKind::Lr0, None);
+        let slr1_parsing_table =
+            grammar.get_parsing_table(ParsingTableKind::Slr1, Some(&first_follow_set));
+        let automaton = get_parsing_automaton(grammar);
+
+        let mut artifacts = vec![];
+        if descriptor.grammar_definition {
+            artifacts.push(Artifact::Grammar);
+        }
+        if descriptor.production_list {
+            artifacts.push(Artifact::Productions);
+        }
+        if descriptor.lr0_parsing_table {
+            artifacts.push(Artifact::ParsingTable(
+                ParsingTableKind::Lr0,
+                &lr0_parsing_table,
+            ));
+        }
+        if descriptor.slr1_parsing_table {
+            artifacts.push(Artifact::ParsingTable(
+                ParsingTableKind::Slr1,
+                &slr1_parsing_table,
+            ));
+        }
+        if descriptor.first_follow_set {
+            artifacts.push(Artifact::FirstFollow(&first_follow_set));
+        }
+        if descriptor.lr0_automaton || (descriptor.graphviz_link && self.reports_automaton()) {
+            artifacts.push(Artifact::Lr0Automaton(&automaton));
+        }
+        if descriptor.item_sets {
+            artifacts.push(Artifact::ItemSets(&automaton));
+        }
+        if descriptor.goto_table {
+            artifacts.push(Artifact::GotoTable(&automaton));
+        }
+        self.render_all(grammar, &artifacts)
+    }
+}
+
+/// Settings shared by all the backends, which ignore the ones not applying to their format
+#[derive(Debug, Clone)]
+pub struct RenderOptions {
+    pub table_style: TableStyle,
+    /// Whether the text backend highlights the conflicts with ANSI colours
+    pub color: bool,
+    /// Whether the LaTeX backend writes a complete document instead of fragments
+    pub standalone: bool,
+    pub language: Language,
+    pub notation: Notation,
+    pub dot: DotStyle,
+}
+
+pub fn get_backend(format: Format, options: &RenderOptions) -> Box<dyn OutputBackend> {
+    match format {
+        Format::Text => Box::new(text::TextBackend {
+            table_style: options.table_style,
+            color: options.color,
+            notation: options.notation.clone(),
+        }),
+        Format::Json => Box::new(json::JsonBackend),
+        Format::Markdown => Box::new(markdown::MarkdownBackend {
+            notation: options.notation.clone(),
+        }),
+        Format::Html => Box::new(html::HtmlBackend {
+            notation: options.notation.clone(),
+        }),
+        Format::Latex => Box::new(latex::LatexBackend {
+            standalone: options.standalone,
+            style: LatexStyle {
+                language: options.language,
+                notation: options.notation.clone(),
+            },
+        }),
+        Format::Dot => Box::new(dot::DotBackend {
+            notation: options.notation.clone(),
+            style: options.dot.clone(),
+        }),
+        Format::Svg => Box::new(svg::SvgBackend {
+            notation: options.notation.clone(),
+        }),
+        Format::Mermaid => Box::new(mermaid::MermaidBackend {
+            notation: options.notation.clone(),
+        }),
+    }
+}
+
+/// A table with plain text cells, shared by the backends which lay out tables on their own
+pub struct Table {
+    pub caption: String,
+    pub header: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    /// Positions `(row, column)` in `rows` of the cells holding more than one action, the
+    /// column of the row label included
+    pub conflicts: Vec<(usize, usize)>,
+    /// Notes on some cells by their position, e.g. the productions of the reduce actions, for
+    /// the backends which can show them without cluttering the table
+    pub notes: HashMap<(usize, usize), String>,
+}
+
+impl Table {
+    pub fn from_productions(grammar: &Grammar, notation: &Notation) -> Table {
+        Table {
+            caption: String::from("Productions"),
+            header: ["#", "Production"].map(String::from).to_vec(),
+            rows: grammar
+                .productions
+                .iter()
+                .enumerate()
+                .map(|(index, production)| {
+                    vec![
+                        notation.production_number(index).to_string(),
+                        notation.production(grammar, production),
+                    ]
+                })
+                .collect(),
+            conflicts: vec![],
+            notes: HashMap::new(),
+        }
+    }
+
+    pub fn from_first_follow(
+        grammar: &Grammar,
+        first_follow_set: &HashMap<char, FirstFollowSet>,
+    ) -> Table {
+        let mut sorted_terms = grammar.get_sorted_terms();
+        sorted_terms.push('$');
+        let format_set = |set: &std::collections::HashSet<char>| {
+            sorted_terms
+                .iter()
+                .filter(|term| set.contains(term))
+                .map(|term| grammar.get_symbol_name(*term))
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+
+        let rows = grammar
+            .get_sorted_non_terms()
+            .iter()
+            .filter_map(|non_term| {
+                let set = first_follow_set.get(non_term)?;
+                Some(vec![
+                    grammar.get_symbol_name(*non_term),
+                    format_set(&set.first),
+                    format_set(&set.follow),
+                    String::from(if set.nullable { "Yes" } else { "No" }),
+                ])
+            })
+            .collect();
+
+        Table {
+            caption: String::from("FIRST and FOLLOW sets"),
+            header: ["Symbol", "First", "Follow", "Nullable"]
+                .map(String::from)
+                .to_vec(),
+            rows,
+            conflicts: vec![],
+            notes: HashMap::new(),
+        }
+    }
+
+    /// Cells of LR tables list their actions separated by `/`, cells of LL(1) tables their
+    /// productions separated by ` / `. Conflicts solved by precedence show the kept action only.
+    pub fn from_parsing_table(
+        grammar: &Grammar,
+        kind: ParsingTableKind,
+        parsing_table: &ParsingTable,
+        notation: &Notation,
+    ) -> Table {
+        let mut sorted_terms = grammar.get_sorted_terms();
+        sorted_terms.push('$');
+        let sorted_non_terms = grammar.get_sorted_non_terms();
+        let caption = format!("{} parsing table", kind);
+
+        match parsing_table {
+            ParsingTable::Ll1(table) => {
+                let header = std::iter::once(String::from("Symbol"))
+                    .chain(
+                        sorted_terms
+                            .iter()
+                            .map(|term| grammar.get_symbol_name(*term)),
+                    )
+                    .collect();
+                let rows = sorted_non_terms
+                    .iter()
+                    .filter_map(|non_term| {
+                        let row = table.get(non_term)?;
+                        let cells = sorted_terms.iter().map(|term| {
+                            row.get(term)
+                                .map(|prod_indices| {
+                                    prod_indices
+                                        .iter()
+                                        .map(|idx| {
+                                            notation.production(grammar, &grammar.productions[*idx])
+                                        })
+                                        .collect::<Vec<String>>()
+                                        .join(" / ")
+                                })
+                                .unwrap_or_default()
+                        });
+                        Some(
+                            std::iter::once(grammar.get_symbol_name(*non_term))
+                                .chain(cells)
+                                .collect(),
+                        )
+                    })
+                    .collect();
+                let conflicts = sorted_non_terms
+                    .iter()
+                    .filter_map(|non_term| table.get(non_term))
+                    .enumerate()
+                    .flat_map(|(row_index, row)| {
+                        sorted_terms
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, term)| row.get(term).is_some_and(|cell| cell.len() > 1))
+                            .map(move |(column, _)| (row_index, column + 1))
+                    })
+                    .collect();
+                Table {
+                    caption,
+                    header,
+                    rows,
+                    conflicts,
+                    notes: HashMap::new(),
+                }
+            }
+            ParsingTable::Lr { rows, resolutions } => {
+                let symbols: Vec<char> = sorted_terms
+                    .iter()
+                    .chain(sorted_non_terms.iter())
+                    .copied()
+                    .collect();
+                let conflicts = rows
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(state, row)| {
+                        symbols
+                            .iter()
+                            .enumerate()
+                            .filter(move |(_, symbol)| {
+                                row.get(symbol).is_some_and(|cell| cell.len() > 1)
+                                    && !resolutions
+                                        .iter()
+                                        .any(|r| r.state == state && r.symbol == **symbol)
+                            })
+                            .map(move |(column, _)| (state, column + 1))
+                    })
+                    .collect();
+                // Cells with reduce actions are annotated with the productions they reduce
+                let notes = rows
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(state, row)| {
+                        symbols
+                            .iter()
+                            .enumerate()
+                            .filter_map(move |(column, symbol)| {
+                                let actions: Vec<&Action> = match resolutions
+                                    .iter()
+                                    .find(|r| r.state == state && r.symbol == *symbol)
+                                {
+                                    Some(resolution) => resolution.kept.iter().collect(),
+                                    None => row.get(symbol).into_iter().flatten().collect(),
+                                };
+                                let reduced: Vec<String> = actions
+                                    .iter()
+                                    .filter_map(|action| match action {
+                                        Action::Reduce(index) => Some(format!(
+                                            "{}: {}",
+                                            notation.action(action),
+                                            notation
+                                                .production(grammar, &grammar.productions[*index])
+                                        )),
+                                        _ => None,
+                                    })
+                                    .collect();
+                                (!reduced.is_empty())
+                                    .then(|| ((state, column + 1), reduced.join("; ")))
+                            })
+                    })
+                    .collect();
+                let header = std::iter::once(String::from("State"))
+                    .chain(
+                        symbols
+                            .iter()
+                            .map(|symbol| grammar.get_symbol_name(*symbol)),
+                    )
+                    .collect();
+                let rows = rows
+                    .iter()
+                    .enumerate()
+                    .map(|(state, row)| {
+                        let cells = symbols.iter().map(|symbol| {
+                            let resolution = resolutions
+                                .iter()
+                                .find(|r| r.state == state && r.symbol == *symbol);
+                            match resolution {
+                                Some(resolution) => resolution
+                                    .kept
+                                    .as_ref()
+                                    .map_or(String::from("err"), |action| notation.action(action)),
+                                None => row
+                                    .get(symbol)
+                                    .map(|actions| {
+                                        actions
+                                            .iter()
+                                            .map(|action| notation.action(action))
+                                            .collect::<Vec<String>>()
+                                            .join("/")
+                                    })
+                                    .unwrap_or_default(),
+                            }
+                        });
+                        std::iter::once(notation.state(state))
+                            .chain(cells)
+                            .collect()
+                    })
+                    .collect();
+                Table {
+                    caption,
+                    header,
+                    rows,
+                    conflicts,
+                    notes,
+                }
+            }
+        }
+    }
+
+    /// The GOTO function of the automaton: a row per state, the terminals and then the non
+    /// terminals as columns and the target state of every transition in the cells
+    pub fn from_goto(grammar: &Grammar, automaton: &AutomatonView) -> Table {
+        let symbols: Vec<String> = grammar
+            .get_sorted_terms()
+            .into_iter()
+            .chain(grammar.get_sorted_non_terms())
+            .map(|symbol| grammar.get_symbol_name(symbol))
+            .collect();
+        let mut header = vec![String::from("State")];
+        header.extend(symbols.iter().cloned());
+        let rows = automaton
+            .states
+            .iter()
+            .enumerate()
+            .map(|(index, state)| {
+                let mut row = vec![state.name.clone()];
+                row.extend(symbols.iter().map(|symbol| {
+                    automaton
+                        .edges
+                        .iter()
+                        .find(|(from, by, _)| *from == index && by == symbol)
+                        .map_or(String::new(), |(_, _, to)| {
+                            automaton.states[*to].name.clone()
+                        })
+                }));
+                row
+            })
+            .collect();
+        Table {
+            caption: String::from("GOTO function"),
+            header,
+            rows,
+            conflicts: vec![],
+            notes: HashMap::new(),
+        }
+    }
+
+    pub fn from_parse_trace(grammar: &Grammar, trace: &ParseTrace, notation: &Notation) -> Table {
+        Table {
+            caption: format!(
+                "{} parsing of {:?}: {}",
+                trace.kind,
+                trace.sentence,
+                trace.outcome.format(grammar, notation)
+            ),
+            header: ["Step", "Stack", "Input", "Action"]
+                .map(String::from)
+                .to_vec(),
+            rows: trace
+                .steps
+                .iter()
+                .enumerate()
+                .map(|(index, step)| {
+                    vec![
+                        (index + 1).to_string(),
+                        step.format_stack(grammar, notation),
+                        step.format_input(grammar),
+                        step.action.format(grammar, notation),
+                    ]
+                })
+                .collect(),
+            conflicts: vec![],
+            notes: HashMap::new(),
+        }
+    }
+
+    pub fn from_classification(
+        grammar: &Grammar,
+        classification: &GrammarClassification,
+        notation: &Notation,
+    ) -> Table {
+        Table {
+            caption: String::from("Classification of the grammar"),
+            header: ["Class", "Member", "Reason"].map(String::from).to_vec(),
+            rows: classification
+                .verdicts
+                .iter()
+                .map(|verdict| {
+                    vec![
+                        verdict.class.to_string(),
+                        String::from(verdict.member_str()),
+                        verdict.reason.format(grammar, notation),
+                    ]
+                })
+                .collect(),
+            conflicts: vec![],
+            notes: HashMap::new(),
+        }
+    }
+}
+
+/// The states of an automaton with their items formatted, shared by the backends which lay out
+/// automata as lists of states
+pub struct AutomatonView {
+    pub title: String,
+    pub states: Vec<StateView>,
+    /// Transitions as `(from, symbol, to)`, sorted by source and target state
+    pub edges: Vec<(usize, String, usize)>,
+}
+
+pub struct StateView {
+    pub name: String,
+    pub kernel: Vec<String>,
+    pub closure: Vec<String>,
+    /// Items of the kernel and of the closure taking part in a conflict of the parsing table
+    /// built from the automaton
+    pub conflicts: Vec<String>,
+    /// Complete items of the kernel and of the closure, the augmented one included
+    pub reduces: Vec<String>,
+    /// Whether the state holds the complete augmented item `S' -> S•`
+    pub accepting: bool,
+}
+
+impl StateView {
+    pub fn has_conflicts(&self) -> bool {
+        !self.conflicts.is_empty()
+    }
+
+    /// The items of the state as `name = { kernel ; closure }`, the closure being left out
+    /// when empty
+    pub fn format_item_set(&self) -> String {
+        if self.closure.is_empty() {
+            format!("{} = {{ {} }}", self.name, self.kernel.join(", "))
+        } else {
+            format!(
+                "{} = {{ {} ; {} }}",
+                self.name,
+                self.kernel.join(", "),
+                self.closure.join(", ")
+            )
+        }
+    }
+}
+
+impl AutomatonView {
+    /// The LR(0) table has no conflicts solved by precedence, so it is built from the automaton
+    pub fn from_lr0(automaton: &Lr0Automaton, notation: &Notation) -> AutomatonView {
+        let grammar = automaton.grammar;
+        let conflicts =
+            automaton.get_conflicting_items(&grammar.get_lr0_parsing_table_from(automaton));
+        AutomatonView {
+            title: String::from("LR(0) automaton"),
+            states: automaton
+                .nodes
+                .iter()
+                .zip(conflicts.iter())
+                .enumerate()
+                .map(|(index, (node, conflicts))| {
+                    let format_items = |items: &[Lr0Item]| -> Vec<String> {
+                        items
+                            .iter()
+                            .map(|item| item.format(grammar, notation))
+                            .collect()
+                    };
+                    let format_filtered = |filter: &dyn Fn(&Lr0Item) -> bool| -> Vec<String> {
+                        node.kernel
+                            .iter()
+                            .chain(node.closure.iter())
+                            .filter(|item| filter(item))
+                            .map(|item| item.format(grammar, notation))
+                            .collect()
+                    };
+                    StateView {
+                        name: notation.state(index),
+                        kernel: format_items(&node.kernel),
+                        closure: format_items(&node.closure),
+                        conflicts: format_filtered(&|item| {
+                            conflicts.contains(&Lr0ItemOwned::from(item))
+                        }),
+                        reduces: format_filtered(&|item| item.is_complete()),
+                        accepting: node
+                            .kernel
+                            .iter()
+                            .chain(node.closure.iter())
+                            .any(|item| item.is_complete() && item.production.index.is_none()),
+                    }
+                })
+                .collect(),
+            edges: Self::get_sorted_edges(grammar, &automaton.edges),
+        }
+    }
+
+    /// Items sharing the same core are listed once, followed by all of their lookaheads. The
+    /// conflicts are the ones left in the given parsing table, whose rows are indexed by the
+    /// states of the automaton.
+    pub fn from_lr1(
+        automaton: &Lr1Automaton,
+        parsing_table: &[HashMap<char, Vec<Action>>],
+        notation: &Notation,
+    ) -> AutomatonView {
+        let conflicts = automaton.get_conflicting_cores(parsing_table);
+        AutomatonView {
+            title: String::from("LR(1) automaton"),
+            states: automaton
+                .nodes
+                .iter()
+                .zip(conflicts.iter())
+                .enumerate()
+                .map(|(index, (node, conflicts))| {
+                    // Filtering whole cores keeps the merged lookaheads of the formatted items
+                    let format_filtered = |filter: &dyn Fn(&Lr1Item) -> bool| -> Vec<String> {
+                        [&node.kernel, &node.closure]
+                            .iter()
+                            .flat_map(|items| {
+                                let items: Vec<Lr1Item> =
+                                    items.iter().filter(|item| filter(item)).cloned().collect();
+                                automaton.format_items(&items, notation)
+                            })
+                            .collect()
+                    };
+                    StateView {
+                        name: notation.state(index),
+                        kernel: automaton.format_items(&node.kernel, notation),
+                        closure: automaton.format_items(&node.closure, notation),
+                        conflicts: format_filtered(&|item| conflicts.contains(&item.core())),
+                        reduces: format_filtered(&|item| item.is_complete()),
+                        accepting: node
+                            .kernel
+                            .iter()
+                            .chain(node.closure.iter())
+                            .any(|item| item.is_complete() && item.production.index.is_none()),
+                    }
+                })
+                .collect(),
+            edges: Self::get_sorted_edges(automaton.grammar, &automaton.edges),
+        }
+    }
+
+    fn get_sorted_edges(
+        grammar: &Grammar,
+        edges: &HashMap<usize, Vec<(usize, char)>>,
+    ) -> Vec<(usize, String, usize)> {
+        get_sorted_edges(edges)
+            .iter()
+            .map(|edge| (edge.from, grammar.get_symbol_name(edge.symbol), edge.to))
+            .collect()
+    }
+}
+
+/// Groups the bodies of the productions by driver, in the order of `get_sorted_non_terms`.
+/// Empty bodies are written with the epsilon of the notation, the symbols of the other ones are
+/// separated by spaces and quoted as in the grammar definition, so that terminals like `|` can't
+/// be mistaken for the separators of the bodies.
+pub fn get_rules(grammar: &Grammar, notation: &Notation) -> Vec<(String, Vec<String>)> {
+    grammar
+        .get_sorted_non_terms()
+        .iter()
+        .map(|driver| {
+            let bodies = grammar
+                .productions
+                .iter()
+                .filter(|production| production.driver == *driver)
+                .map(|production| {
+                    if production.body.is_empty() {
+                        notation.epsilon.clone()
+                    } else {
+                        production
+                            .body
+                            .iter()
+                            .map(|symbol| grammar.get_definition_symbol(*symbol))
+                            .collect::<Vec<String>>()
+                            .join(" ")
+                    }
+                })
+                .collect();
+            (grammar.get_symbol_name(*driver), bodies)
+        })
+        .collect()
+}
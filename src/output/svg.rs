@@ -0,0 +1,396 @@
+use std::collections::{BTreeMap, HashMap};
+
+use super::html::escape_html;
+use super::layout::{LayeredGraph, Vertex};
+use super::{AutomatonView, OutputBackend, StateView};
+use crate::grammar::{
+    classify::GrammarClassification,
+    grammar::Grammar,
+    notation::Notation,
+    parse_structs::{Action, FirstFollowSet, ParsingTable, ParsingTableKind},
+    simulate::ParseTrace,
+};
+use crate::item_nfa::SubsetConstruction;
+use crate::lr0::Lr0Automaton;
+use crate::lr1::Lr1Automaton;
+
+const FONT_SIZE: f64 = 12.0;
+/// Width of a character of the monospaced font, since the text can't be measured
+const CHAR_WIDTH: f64 = 7.2;
+const LINE_HEIGHT: f64 = 16.0;
+const PADDING: f64 = 6.0;
+const MARGIN: f64 = 20.0;
+/// Horizontal distance between two layers of states
+const LAYER_GAP: f64 = 90.0;
+/// Vertical distance between two states of the same layer
+const NODE_GAP: f64 = 40.0;
+/// Distance of the control point of the curved edges from the straight line
+const CURVE_OFFSET: f64 = 40.0;
+const LOOP_HEIGHT: f64 = 30.0;
+
+/// Standalone SVG images, only available for the automata, drawn without Graphviz
+pub struct SvgBackend {
+    pub notation: Notation,
+}
+
+/// A state drawn as a record like the ones of `generate_dot_notation_string`: its name, its
+/// kernel and (if not empty) its closure, one below the other. The lines of the conflicting items
+/// are underlined.
+struct NodeBox {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    sections: Vec<Vec<(String, bool)>>,
+    has_conflicts: bool,
+}
+
+impl NodeBox {
+    fn new(state: &StateView) -> NodeBox {
+        let format_items = |items: &[String]| -> Vec<(String, bool)> {
+            items
+                .iter()
+                .map(|item| (item.replace("->", "→"), state.conflicts.contains(item)))
+                .collect()
+        };
+        let mut sections = vec![
+            vec![(state.name.clone(), false)],
+            format_items(&state.kernel),
+        ];
+        if !state.closure.is_empty() {
+            sections.push(format_items(&state.closure));
+        }
+
+        let max_chars = sections
+            .iter()
+            .flatten()
+            .map(|(line, _)| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let lines: usize = sections.iter().map(Vec::len).sum();
+        NodeBox {
+            x: 0.0,
+            y: 0.0,
+            width: max_chars as f64 * CHAR_WIDTH + 2.0 * PADDING,
+            height: lines as f64 * LINE_HEIGHT + sections.len() as f64 * 2.0 * PADDING,
+            sections,
+            has_conflicts: state.has_conflicts(),
+        }
+    }
+
+    fn center(&self) -> (f64, f64) {
+        (self.x + self.width / 2.0, self.y + self.height / 2.0)
+    }
+
+    /// The point where the segment from the center to `(x, y)` crosses the border
+    fn border_point(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        let (cx, cy) = self.center();
+        let (dx, dy) = (x - cx, y - cy);
+        if dx == 0.0 && dy == 0.0 {
+            return (cx, cy);
+        }
+        let scale_x = if dx == 0.0 {
+            f64::INFINITY
+        } else {
+            (self.width / 2.0) / dx.abs()
+        };
+        let scale_y = if dy == 0.0 {
+            f64::INFINITY
+        } else {
+            (self.height / 2.0) / dy.abs()
+        };
+        let scale = scale_x.min(scale_y);
+        (cx + dx * scale, cy + dy * scale)
+    }
+
+    fn render(&self) -> String {
+        let mut rv = format!(
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" class=\"{}\"/>\n",
+            self.x,
+            self.y,
+            self.width,
+            self.height,
+            if self.has_conflicts {
+                "state conflict"
+            } else {
+                "state"
+            }
+        );
+        let mut y = self.y;
+        for (index, section) in self.sections.iter().enumerate() {
+            if index > 0 {
+                rv.push_str(&format!(
+                    "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" class=\"state\"/>\n",
+                    self.x,
+                    y,
+                    self.x + self.width,
+                    y
+                ));
+            }
+            y += PADDING;
+            for (line, underlined) in section {
+                y += LINE_HEIGHT;
+                // The name of the state is centered, the items are aligned to the left
+                let (x, anchor) = if index == 0 {
+                    (self.x + self.width / 2.0, "middle")
+                } else {
+                    (self.x + PADDING, "start")
+                };
+                rv.push_str(&format!(
+                    "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"{}\"{}>{}</text>\n",
+                    x,
+                    y - 4.0,
+                    anchor,
+                    if *underlined {
+                        " text-decoration=\"underline\""
+                    } else {
+                        ""
+                    },
+                    escape_html(line)
+                ));
+            }
+            y += PADDING;
+        }
+        rv
+    }
+}
+
+/// Renders the automaton as a standalone SVG image, laid out with `LayeredGraph` left to right
+/// from the initial state. Edges between the same states are merged into one labelled with all
+/// of their symbols; the ones spanning several layers are drawn through their dummy vertices.
+/// States with conflicts have a red border.
+pub fn render_automaton(automaton: &AutomatonView) -> String {
+    let mut nodes: Vec<NodeBox> = automaton.states.iter().map(NodeBox::new).collect();
+    let mut merged_edges: BTreeMap<(usize, usize), Vec<&str>> = BTreeMap::new();
+    for (from, symbol, to) in automaton.edges.iter() {
+        merged_edges.entry((*from, *to)).or_default().push(symbol);
+    }
+    let pairs: Vec<(usize, usize)> = merged_edges.keys().copied().collect();
+    let graph = LayeredGraph::new(nodes.len(), &pairs);
+    let sizes: Vec<(f64, f64)> = nodes.iter().map(|node| (node.width, node.height)).collect();
+    let placement = graph.place(&sizes, LAYER_GAP, NODE_GAP);
+
+    // Room is left above the states for the self loops and around them for the curved edges
+    let offset = |(x, y): (f64, f64)| (x + MARGIN, y + MARGIN + LOOP_HEIGHT);
+    for (index, node) in nodes.iter_mut().enumerate() {
+        let (x, y) = offset(placement.centers[&Vertex::Node(index)]);
+        node.x = x - node.width / 2.0;
+        node.y = y - node.height / 2.0;
+    }
+    let width = placement.width + 2.0 * MARGIN;
+    let height = placement.height + 2.0 * MARGIN + 2.0 * LOOP_HEIGHT;
+
+    let mut rv = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{:.0}\" height=\"{:.0}\" \
+         viewBox=\"0 0 {:.0} {:.0}\" font-family=\"monospace\" font-size=\"{}\">\n",
+        width, height, width, height, FONT_SIZE
+    );
+    rv.push_str(&format!(
+        "<title>{}</title>\n",
+        escape_html(&automaton.title)
+    ));
+    rv.push_str(
+        "<defs><marker id=\"arrow\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" \
+         markerWidth=\"8\" markerHeight=\"8\" orient=\"auto-start-reverse\">\
+         <path d=\"M 0 0 L 10 5 L 0 10 z\"/></marker></defs>\n",
+    );
+    rv.push_str(
+        "<style>.state { fill: white; stroke: black; } \
+         .conflict { stroke: red; stroke-width: 2; } \
+         .edge { fill: none; stroke: black; stroke-linejoin: round; \
+         marker-end: url(#arrow); }</style>\n",
+    );
+
+    for (((from, to), symbols), route) in merged_edges.iter().zip(graph.routes.iter()) {
+        let label = escape_html(&symbols.join(", "));
+        let (path, (label_x, label_y)) = if from == to {
+            let node = &nodes[*from];
+            let (left, right) = (node.x + node.width * 0.4, node.x + node.width * 0.6);
+            (
+                format!(
+                    "M {:.1} {:.1} C {:.1} {:.1} {:.1} {:.1} {:.1} {:.1}",
+                    left,
+                    node.y,
+                    left,
+                    node.y - LOOP_HEIGHT,
+                    right,
+                    node.y - LOOP_HEIGHT,
+                    right,
+                    node.y
+                ),
+                (node.x + node.width / 2.0, node.y - LOOP_HEIGHT * 0.75 - 4.0),
+            )
+        } else if !route.is_empty() {
+            // A polyline through the dummy vertices, labelled in the middle
+            let (source, target) = (&nodes[*from], &nodes[*to]);
+            let waypoints: Vec<(f64, f64)> = route
+                .iter()
+                .map(|vertex| offset(placement.centers[vertex]))
+                .collect();
+            let mut points = vec![source.border_point(waypoints[0])];
+            points.extend(waypoints.iter().copied());
+            points.push(target.border_point(waypoints[waypoints.len() - 1]));
+            let middle = (points.len() - 1) / 2;
+            (
+                points
+                    .iter()
+                    .enumerate()
+                    .map(|(index, (x, y))| {
+                        format!("{} {:.1} {:.1}", if index == 0 { "M" } else { "L" }, x, y)
+                    })
+                    .collect::<Vec<String>>()
+                    .join(" "),
+                (
+                    (points[middle].0 + points[middle + 1].0) / 2.0,
+                    (points[middle].1 + points[middle + 1].1) / 2.0 - 4.0,
+                ),
+            )
+        } else {
+            let (source, target) = (&nodes[*from], &nodes[*to]);
+            let (start, end) = (
+                source.border_point(target.center()),
+                target.border_point(source.center()),
+            );
+            // Edges going backwards (or within a layer) are bent, so that they don't overlap
+            // the ones going forwards between the same states
+            let offset = if graph.layers[*to] > graph.layers[*from] {
+                0.0
+            } else {
+                CURVE_OFFSET
+            };
+            let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+            let length = (dx * dx + dy * dy).sqrt().max(1.0);
+            let control = (
+                (start.0 + end.0) / 2.0 - dy / length * offset,
+                (start.1 + end.1) / 2.0 + dx / length * offset,
+            );
+            (
+                format!(
+                    "M {:.1} {:.1} Q {:.1} {:.1} {:.1} {:.1}",
+                    start.0, start.1, control.0, control.1, end.0, end.1
+                ),
+                (
+                    (start.0 + 2.0 * control.0 + end.0) / 4.0,
+                    (start.1 + 2.0 * control.1 + end.1) / 4.0 - 4.0,
+                ),
+            )
+        };
+        rv.push_str(&format!("<path d=\"{}\" class=\"edge\"/>\n", path));
+        rv.push_str(&format!(
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text>\n",
+            label_x, label_y, label
+        ));
+    }
+    for node in nodes.iter() {
+        rv.push_str(&node.render());
+    }
+    rv.push_str("</svg>");
+    rv
+}
+
+impl OutputBackend for SvgBackend {
+    fn grammar(&self, _grammar: &Grammar) -> Option<String> {
+        None
+    }
+
+    fn productions(&self, _grammar: &Grammar) -> Option<String> {
+        None
+    }
+
+    fn first_follow(
+        &self,
+        _grammar: &Grammar,
+        _first_follow_set: &HashMap<char, FirstFollowSet>,
+    ) -> Option<String> {
+        None
+    }
+
+    fn parsing_table(
+        &self,
+        _grammar: &Grammar,
+        _kind: ParsingTableKind,
+        _parsing_table: &ParsingTable,
+    ) -> Option<String> {
+        None
+    }
+
+    fn lr0_automaton(&self, automaton: &Lr0Automaton) -> Option<String> {
+        Some(render_automaton(&AutomatonView::from_lr0(
+            automaton,
+            &self.notation,
+        )))
+    }
+
+    fn lr1_automaton(
+        &self,
+        automaton: &Lr1Automaton,
+        parsing_table: &[HashMap<char, Vec<Action>>],
+    ) -> Option<String> {
+        Some(render_automaton(&AutomatonView::from_lr1(
+            automaton,
+            parsing_table,
+            &self.notation,
+        )))
+    }
+
+    fn item_sets(&self, _automaton: &Lr0Automaton) -> Option<String> {
+        None
+    }
+
+    fn goto_table(&self, _automaton: &Lr0Automaton) -> Option<String> {
+        None
+    }
+
+    fn subset_construction(
+        &self,
+        _grammar: &Grammar,
+        _construction: &SubsetConstruction,
+    ) -> Option<String> {
+        None
+    }
+
+    fn parse_trace(&self, _grammar: &Grammar, _trace: &ParseTrace) -> Option<String> {
+        None
+    }
+
+    fn classification(
+        &self,
+        _grammar: &Grammar,
+        _classification: &GrammarClassification,
+    ) -> Option<String> {
+        None
+    }
+
+    fn sentences(&self, _grammar: &Grammar, _sentences: &[Vec<char>]) -> Option<String> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grammar::create_grammar::create_grammar_from_str;
+    use crate::lr0::get_parsing_automaton;
+
+    #[test]
+    fn conflict_states_have_a_red_border() {
+        let backend = SvgBackend {
+            notation: Notation::default(),
+        };
+        let grammar = create_grammar_from_str("E -> E + E | a .").unwrap();
+        let svg = backend
+            .lr0_automaton(&get_parsing_automaton(&grammar))
+            .unwrap();
+
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.ends_with("</svg>"));
+        assert!(svg.contains(".conflict { stroke: red;"));
+        assert_eq!(svg.matches("class=\"state conflict\"").count(), 1);
+
+        let grammar = create_grammar_from_str("E -> E + a | a .").unwrap();
+        let svg = backend
+            .lr0_automaton(&get_parsing_automaton(&grammar))
+            .unwrap();
+        assert!(!svg.contains("class=\"state conflict\""));
+    }
+}
@@ -0,0 +1,336 @@
+use std::collections::HashMap;
+
+use super::{AutomatonView, OutputBackend, Table};
+use crate::args::command::TableStyle;
+use crate::grammar::{
+    classify::GrammarClassification,
+    grammar::Grammar,
+    notation::Notation,
+    parse_structs::{Action, FirstFollowSet, ParsingTable, ParsingTableKind},
+    simulate::ParseTrace,
+};
+use crate::item_nfa::SubsetConstruction;
+use crate::lr0::Lr0Automaton;
+use crate::lr1::Lr1Automaton;
+
+/// ANSI escape sequences highlighting a conflicting cell (bold red) and restoring the style
+const CONFLICT_STYLE: &str = "\x1b[1;31m";
+const RESET_STYLE: &str = "\x1b[0m";
+
+/// Characters drawing the borders of a table: the horizontal line, the vertical line and the
+/// corners and junctions of the top, middle and bottom rules as `[left, middle, right]`
+struct BoxChars {
+    horizontal: char,
+    vertical: char,
+    top: [char; 3],
+    middle: [char; 3],
+    bottom: [char; 3],
+}
+
+const ASCII_BOX: BoxChars = BoxChars {
+    horizontal: '-',
+    vertical: '|',
+    top: ['+', '+', '+'],
+    middle: ['+', '+', '+'],
+    bottom: ['+', '+', '+'],
+};
+
+const UNICODE_BOX: BoxChars = BoxChars {
+    horizontal: '─',
+    vertical: '│',
+    top: ['┌', '┬', '┐'],
+    middle: ['├', '┼', '┤'],
+    bottom: ['└', '┴', '┘'],
+};
+
+/// Plain text meant to be read in a terminal, with tables laid out in aligned columns
+pub struct TextBackend {
+    pub table_style: TableStyle,
+    /// Whether the conflicting cells are highlighted with ANSI colours
+    pub color: bool,
+    pub notation: Notation,
+}
+
+impl TextBackend {
+    fn format_table(&self, table: &Table) -> String {
+        let mut widths: Vec<usize> = table
+            .header
+            .iter()
+            .map(|cell| cell.chars().count())
+            .collect();
+        for row in table.rows.iter() {
+            for (width, cell) in widths.iter_mut().zip(row.iter()) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        // Cells are padded before being coloured, since the escape sequences take no room
+        let format_cell = |row_index: Option<usize>, column: usize, cell: &str| {
+            let padded = format!("{:<width$}", cell, width = widths[column]);
+            match row_index {
+                Some(row_index) if self.color && table.conflicts.contains(&(row_index, column)) => {
+                    format!("{}{}{}", CONFLICT_STYLE, padded, RESET_STYLE)
+                }
+                _ => padded,
+            }
+        };
+        let format_row = |row_index: Option<usize>, row: &[String]| -> Vec<String> {
+            row.iter()
+                .enumerate()
+                .map(|(column, cell)| format_cell(row_index, column, cell))
+                .collect()
+        };
+
+        let mut lines = vec![table.caption.clone()];
+        let box_chars = match self.table_style {
+            TableStyle::Plain => {
+                lines.push(
+                    format_row(None, &table.header)
+                        .join("  ")
+                        .trim_end()
+                        .to_string(),
+                );
+                for (row_index, row) in table.rows.iter().enumerate() {
+                    let line = format_row(Some(row_index), row).join("  ");
+                    lines.push(line.trim_end().to_string());
+                }
+                return lines.join("\n");
+            }
+            TableStyle::Ascii => &ASCII_BOX,
+            TableStyle::Unicode => &UNICODE_BOX,
+        };
+
+        let rule = |[left, middle, right]: [char; 3]| {
+            let segments: Vec<String> = widths
+                .iter()
+                .map(|width| box_chars.horizontal.to_string().repeat(width + 2))
+                .collect();
+            format!("{}{}{}", left, segments.join(&middle.to_string()), right)
+        };
+        let separator = format!(" {} ", box_chars.vertical);
+        let bordered = |cells: Vec<String>| {
+            format!(
+                "{} {} {}",
+                box_chars.vertical,
+                cells.join(&separator),
+                box_chars.vertical
+            )
+        };
+
+        lines.push(rule(box_chars.top));
+        lines.push(bordered(format_row(None, &table.header)));
+        lines.push(rule(box_chars.middle));
+        for (row_index, row) in table.rows.iter().enumerate() {
+            lines.push(bordered(format_row(Some(row_index), row)));
+        }
+        lines.push(rule(box_chars.bottom));
+        lines.join("\n")
+    }
+
+    fn format_automaton(automaton: &AutomatonView) -> String {
+        let mut lines = vec![automaton.title.clone()];
+        for (index, state) in automaton.states.iter().enumerate() {
+            lines.push(format!("State {}", state.name));
+            lines.extend(state.kernel.iter().map(|item| format!("    {}", item)));
+            lines.extend(state.closure.iter().map(|item| format!("  + {}", item)));
+            lines.extend(
+                automaton
+                    .edges
+                    .iter()
+                    .filter(|(from, _, _)| *from == index)
+                    .map(|(_, symbol, to)| {
+                        format!("  on {} go to {}", symbol, automaton.states[*to].name)
+                    }),
+            );
+        }
+        lines.join("\n")
+    }
+}
+
+impl TextBackend {
+    /// Lists the states of the item NFA with their transitions, the steps of the subset
+    /// construction and the state of the LR(0) automaton every state of the DFA is
+    fn format_subset_construction(
+        &self,
+        grammar: &Grammar,
+        construction: &SubsetConstruction,
+    ) -> String {
+        let nfa = &construction.nfa;
+        let epsilon = &self.notation.epsilon;
+        let format_set = |states: &[usize]| -> String {
+            let items: Vec<String> = states
+                .iter()
+                .map(|state| nfa.items[*state].format(grammar, &self.notation))
+                .collect();
+            format!("{{ {} }}", items.join(", "))
+        };
+        let dfa_state = |index: usize| format!("D{}", index);
+
+        let mut lines = vec![String::from("Item NFA")];
+        for (index, item) in nfa.items.iter().enumerate() {
+            lines.push(format!(
+                "{:>4}  {}",
+                index,
+                item.format(grammar, &self.notation)
+            ));
+            lines.extend(
+                nfa.edges
+                    .iter()
+                    .filter(|edge| edge.from == index)
+                    .map(|edge| match edge.symbol {
+                        Some(symbol) => format!(
+                            "        on {} go to {}",
+                            grammar.get_symbol_name(symbol),
+                            edge.to
+                        ),
+                        None => format!("        on {} go to {}", epsilon, edge.to),
+                    }),
+            );
+        }
+
+        lines.push(String::new());
+        lines.push(String::from("Subset construction"));
+        for step in construction.steps.iter() {
+            let target = format!(
+                "{}{}",
+                dfa_state(step.to),
+                if step.is_new { " (new)" } else { "" }
+            );
+            match (step.from, step.symbol) {
+                (Some(from), Some(symbol)) => lines.push(format!(
+                    "  {} on {}: {}, {}-closure {} = {}",
+                    dfa_state(from),
+                    grammar.get_symbol_name(symbol),
+                    format_set(&step.moved),
+                    epsilon,
+                    format_set(&step.closure),
+                    target
+                )),
+                _ => lines.push(format!(
+                    "  {}-closure {} = {} = {}",
+                    epsilon,
+                    format_set(&step.moved),
+                    format_set(&step.closure),
+                    target
+                )),
+            }
+        }
+
+        lines.push(String::new());
+        lines.push(String::from("States of the LR(0) automaton"));
+        for (index, lr0_state) in construction.lr0_states.iter().enumerate() {
+            lines.push(format!(
+                "  {} = {}",
+                dfa_state(index),
+                lr0_state.map_or(String::from("none"), |state| self.notation.state(state))
+            ));
+        }
+        lines.push(String::from(if construction.matches_lr0_automaton() {
+            "The subset construction yields the states of the LR(0) automaton"
+        } else {
+            "The subset construction doesn't yield the states of the LR(0) automaton"
+        }));
+        lines.join("\n")
+    }
+}
+
+impl OutputBackend for TextBackend {
+    /// The grammar is written in the format it is read from
+    fn grammar(&self, grammar: &Grammar) -> Option<String> {
+        Some(
+            grammar
+                .get_definition_string()
+                .trim_end_matches('\n')
+                .to_string(),
+        )
+    }
+
+    fn productions(&self, grammar: &Grammar) -> Option<String> {
+        Some(self.format_table(&Table::from_productions(grammar, &self.notation)))
+    }
+
+    fn first_follow(
+        &self,
+        grammar: &Grammar,
+        first_follow_set: &HashMap<char, FirstFollowSet>,
+    ) -> Option<String> {
+        Some(self.format_table(&Table::from_first_follow(grammar, first_follow_set)))
+    }
+
+    fn parsing_table(
+        &self,
+        grammar: &Grammar,
+        kind: ParsingTableKind,
+        parsing_table: &ParsingTable,
+    ) -> Option<String> {
+        Some(self.format_table(&Table::from_parsing_table(
+            grammar,
+            kind,
+            parsing_table,
+            &self.notation,
+        )))
+    }
+
+    fn lr0_automaton(&self, automaton: &Lr0Automaton) -> Option<String> {
+        Some(Self::format_automaton(&AutomatonView::from_lr0(
+            automaton,
+            &self.notation,
+        )))
+    }
+
+    fn lr1_automaton(
+        &self,
+        automaton: &Lr1Automaton,
+        parsing_table: &[HashMap<char, Vec<Action>>],
+    ) -> Option<String> {
+        Some(Self::format_automaton(&AutomatonView::from_lr1(
+            automaton,
+            parsing_table,
+            &self.notation,
+        )))
+    }
+
+    fn item_sets(&self, automaton: &Lr0Automaton) -> Option<String> {
+        let automaton = AutomatonView::from_lr0(automaton, &self.notation);
+        let mut lines = vec![String::from("LR(0) item sets")];
+        lines.extend(automaton.states.iter().map(|state| state.format_item_set()));
+        Some(lines.join("\n"))
+    }
+
+    fn goto_table(&self, automaton: &Lr0Automaton) -> Option<String> {
+        Some(self.format_table(&Table::from_goto(
+            automaton.grammar,
+            &AutomatonView::from_lr0(automaton, &self.notation),
+        )))
+    }
+
+    fn subset_construction(
+        &self,
+        grammar: &Grammar,
+        construction: &SubsetConstruction,
+    ) -> Option<String> {
+        Some(self.format_subset_construction(grammar, construction))
+    }
+
+    fn parse_trace(&self, grammar: &Grammar, trace: &ParseTrace) -> Option<String> {
+        Some(trace.generate_text_string(grammar, &self.notation))
+    }
+
+    fn classification(
+        &self,
+        grammar: &Grammar,
+        classification: &GrammarClassification,
+    ) -> Option<String> {
+        Some(classification.generate_text_string(grammar, &self.notation))
+    }
+
+    fn sentences(&self, grammar: &Grammar, sentences: &[Vec<char>]) -> Option<String> {
+        Some(
+            sentences
+                .iter()
+                .map(|sentence| grammar.format_sentence(sentence))
+                .collect::<Vec<String>>()
+                .join("\n"),
+        )
+    }
+}
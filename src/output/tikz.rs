@@ -0,0 +1,139 @@
+use std::collections::BTreeMap;
+
+use super::AutomatonView;
+use super::layout::{LayeredGraph, Vertex};
+use crate::grammar::latex::escape_latex;
+
+/// Horizontal distance between two layers of states
+const LAYER_GAP: &str = "1.5cm";
+/// Vertical distance between two states of the same layer
+const NODE_GAP: &str = "0.8cm";
+
+/// Renders the automaton as a floating figure, shrunk to the width of the text when larger
+pub fn render_figure(automaton: &AutomatonView, caption: &str, label: &str) -> String {
+    format!(
+        "\\begin{{figure}}[H]\\centering\n\\begin{{adjustbox}}{{max width=\\linewidth}}\n{}\n\
+         \\end{{adjustbox}}\n\\caption{{{}}}\\label{{fig:{}}}\\end{{figure}}",
+        render_automaton(automaton),
+        escape_latex(caption),
+        label
+    )
+}
+
+/// Name of the TikZ node of a state, independent of the notation
+fn node_name(index: usize) -> String {
+    format!("q{}", index)
+}
+
+/// Renders the automaton as a `tikzpicture`. As in the SVG rendering the states are laid out
+/// left to right in the layers of a `LayeredGraph`: the first state of each layer is placed to
+/// the right of everything drawn before it, the others below it in the order of the layer. Every state
+/// is split like the records of `generate_dot_notation_string` into its name, its kernel and
+/// (if not empty) its closure. States with conflicts have a red border and their conflicting
+/// items underlined.
+pub fn render_automaton(automaton: &AutomatonView) -> String {
+    let mut merged_edges: BTreeMap<(usize, usize), Vec<&str>> = BTreeMap::new();
+    for (from, symbol, to) in automaton.edges.iter() {
+        merged_edges.entry((*from, *to)).or_default().push(symbol);
+    }
+    let pairs: Vec<(usize, usize)> = merged_edges.keys().copied().collect();
+    let graph = LayeredGraph::new(automaton.states.len(), &pairs);
+
+    let mut rv = format!(
+        "\\begin{{tikzpicture}}[>=stealth, node distance={} and {}, \
+         every state/.style={{rectangle split, rectangle split parts=3, draw, align=left, \
+         font=\\footnotesize, inner sep=3pt, minimum size=0pt}}, \
+         initial text=]\n",
+        NODE_GAP, LAYER_GAP
+    );
+    for (layer, rank) in graph.ranks.iter().enumerate() {
+        let mut previous: Option<usize> = None;
+        for vertex in rank.iter() {
+            let Vertex::Node(index) = vertex else {
+                continue;
+            };
+            let state = &automaton.states[*index];
+            let mut options = vec![String::from("state")];
+            if *index == 0 {
+                options.push(String::from("initial"));
+            }
+            if state.closure.is_empty() {
+                options.push(String::from("rectangle split parts=2"));
+            }
+            if state.has_conflicts() {
+                options.push(String::from("draw=red, thick"));
+            }
+            let position = match previous {
+                Some(previous) => format!(
+                    "below={} of {}.south west, anchor=north west",
+                    NODE_GAP,
+                    node_name(previous)
+                ),
+                None if layer == 0 => String::from("anchor=north west"),
+                None => format!("anchor=north west, xshift={}", LAYER_GAP),
+            };
+            options.push(position);
+            let at = match previous {
+                None if layer > 0 => {
+                    format!(" at (current bounding box.east |- {}.north)", node_name(0))
+                }
+                _ => String::new(),
+            };
+
+            let format_items = |items: &[String]| {
+                items
+                    .iter()
+                    .map(|item| {
+                        if state.conflicts.contains(item) {
+                            format!("\\underline{{{}}}", escape_latex(item))
+                        } else {
+                            escape_latex(item)
+                        }
+                    })
+                    .collect::<Vec<String>>()
+                    .join("\\\\ ")
+            };
+            let mut text = format!(
+                "{} \\nodepart{{two}} {}",
+                escape_latex(&state.name),
+                format_items(&state.kernel)
+            );
+            if !state.closure.is_empty() {
+                text.push_str(&format!(
+                    " \\nodepart{{three}} {}",
+                    format_items(&state.closure)
+                ));
+            }
+            rv.push_str(&format!(
+                "\\node[{}] ({}){} {{{}}};\n",
+                options.join(", "),
+                node_name(*index),
+                at,
+                text
+            ));
+            previous = Some(*index);
+        }
+    }
+
+    rv.push_str("\\path[->]\n");
+    for ((from, to), symbols) in merged_edges.iter() {
+        // Edges going backwards (or within a layer) are bent, so that they don't overlap the
+        // ones going forwards between the same states
+        let style = if from == to {
+            "loop above"
+        } else if graph.layers[*to] > graph.layers[*from] {
+            ""
+        } else {
+            "bend left"
+        };
+        rv.push_str(&format!(
+            "    ({}) edge[{}] node[auto] {{{}}} ({})\n",
+            node_name(*from),
+            style,
+            escape_latex(&symbols.join(", ")),
+            node_name(*to)
+        ));
+    }
+    rv.push_str(";\n\\end{tikzpicture}");
+    rv
+}
@@ -1,4 +1,7 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
 use petgraph::{Graph, visit::EdgeRef};
+use serde::{Serialize, Serializer};
 
 pub fn get_dot_from_petgraph<T>(graph: &Graph<T, ()>) -> String
 where
@@ -20,9 +23,58 @@ where
     rv
 }
 
-pub fn truncate_after_last(s: &str, c: char) -> String {
-    match s.rfind(c) {
-        Some(index) => s[..=index].to_string(), // Include the character
-        None => s.to_string(),
-    }
+/// Serializes a set with its elements sorted, so that the output doesn't depend on hashing
+pub fn serialize_sorted_set<S, T>(set: &HashSet<T>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Ord + Serialize,
+{
+    let mut elements: Vec<&T> = set.iter().collect();
+    elements.sort();
+    elements.serialize(serializer)
+}
+
+/// Serializes a map with its keys sorted, so that the output doesn't depend on hashing
+pub fn serialize_sorted_map<S, K, V>(map: &HashMap<K, V>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    K: Ord + Serialize,
+    V: Serialize,
+{
+    map.iter()
+        .collect::<BTreeMap<&K, &V>>()
+        .serialize(serializer)
+}
+
+/// Serializes a map of maps with the keys of both levels sorted
+pub fn serialize_sorted_nested_map<S, K, L, V>(
+    map: &HashMap<K, HashMap<L, V>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    K: Ord + Serialize,
+    L: Ord + Serialize,
+    V: Serialize,
+{
+    map.iter()
+        .map(|(key, inner)| (key, inner.iter().collect::<BTreeMap<&L, &V>>()))
+        .collect::<BTreeMap<&K, BTreeMap<&L, &V>>>()
+        .serialize(serializer)
+}
+
+/// Serializes a sequence of maps with the keys of each map sorted
+pub fn serialize_sorted_maps<S, K, V>(
+    maps: &[HashMap<K, V>],
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    K: Ord + Serialize,
+    V: Serialize,
+{
+    maps.iter()
+        .map(|map| map.iter().collect::<BTreeMap<&K, &V>>())
+        .collect::<Vec<BTreeMap<&K, &V>>>()
+        .serialize(serializer)
 }